@@ -1,19 +1,30 @@
+use crate::game::simulating;
 use bevy::app::AppExit;
 use bevy::prelude::*;
 
+const GAME_OVER_DELAY: f32 = 1.5;
+
 pub struct AppStatePlugin;
 
 impl Plugin for AppStatePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<AppState>().add_event::<GameOver>().add_systems(
-            Update,
-            (
-                exit_event,
-                handle_game_over,
-                transition_to_game_state,
-                transition_to_main_menu_state,
-            ),
-        );
+        app.init_state::<AppState>()
+            .add_event::<GameOver>()
+            .add_event::<Victory>()
+            .add_event::<TimeUp>()
+            .add_systems(OnExit(AppState::Game), cancel_game_over_timer)
+            .add_systems(
+                Update,
+                (
+                    exit_event,
+                    handle_game_over,
+                    tick_game_over_timer.run_if(not(simulating)),
+                    handle_victory,
+                    handle_time_up,
+                    transition_to_game_state,
+                    transition_to_main_menu_state,
+                ),
+            );
     }
 }
 
@@ -21,13 +32,30 @@ impl Plugin for AppStatePlugin {
 pub enum AppState {
     #[default]
     MainMenu,
+    LevelSelect,
     Game,
     GameOver,
+    Victory,
+    Editor,
+    ModeSelect,
+    Daily,
+    Online,
 }
 
 #[derive(Event)]
 pub struct GameOver;
 
+#[derive(Event)]
+pub struct Victory;
+
+#[derive(Event)]
+pub struct TimeUp;
+
+#[derive(Resource)]
+struct GameOverTimer {
+    timer: Timer,
+}
+
 fn transition_to_game_state(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     state: ResMut<State<AppState>>,
@@ -58,8 +86,44 @@ fn exit_event(keyboard_input: Res<ButtonInput<KeyCode>>, mut event_writer: Event
     }
 }
 
-fn handle_game_over(mut event_reader: EventReader<GameOver>, mut next_state: ResMut<NextState<AppState>>) {
-    for _game_over_event in event_reader.read() {
+fn handle_game_over(
+    mut commands: Commands,
+    mut event_reader: EventReader<GameOver>,
+    game_over_timer: Option<Res<GameOverTimer>>,
+) {
+    if event_reader.read().count() > 0 && game_over_timer.is_none() {
+        commands.insert_resource(GameOverTimer {
+            timer: Timer::from_seconds(GAME_OVER_DELAY, TimerMode::Once),
+        });
+    }
+}
+
+fn tick_game_over_timer(
+    mut commands: Commands,
+    game_over_timer: Option<ResMut<GameOverTimer>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    time: Res<Time<Real>>,
+) {
+    if let Some(mut game_over_timer) = game_over_timer
+        && game_over_timer.timer.tick(time.delta()).finished()
+    {
+        commands.remove_resource::<GameOverTimer>();
         next_state.set(AppState::GameOver);
     }
 }
+
+fn handle_victory(mut event_reader: EventReader<Victory>, mut next_state: ResMut<NextState<AppState>>) {
+    for _victory_event in event_reader.read() {
+        next_state.set(AppState::Victory);
+    }
+}
+
+fn handle_time_up(mut event_reader: EventReader<TimeUp>, mut next_state: ResMut<NextState<AppState>>) {
+    for _time_up_event in event_reader.read() {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+pub fn cancel_game_over_timer(mut commands: Commands) {
+    commands.remove_resource::<GameOverTimer>();
+}
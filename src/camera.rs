@@ -0,0 +1,197 @@
+use crate::app_state::{AppState, GameOver};
+use crate::game::{Arena, CollectStarEvent, PlayerHitEvent, RollbackFrame, simulating};
+use crate::settings::{CameraScaling, Settings};
+use bevy::prelude::*;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::window::PrimaryWindow;
+use rand::random;
+
+const HIT_TRAUMA: f32 = 0.8;
+const TRAUMA_DECAY: f32 = 1.2;
+const MAX_SHAKE_OFFSET: f32 = 24.0;
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+const PUNCH_ZOOM: f32 = 0.04;
+const PUNCH_DECAY: f32 = 8.0;
+const GAME_OVER_ZOOM: f32 = 0.35;
+const GAME_OVER_ZOOM_SPEED: f32 = 1.5;
+const SLOW_MOTION_SPEED: f32 = 0.25;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Game), reset_camera_effects)
+            .add_systems(OnExit(AppState::Game), reset_camera_effects)
+            .add_systems(
+                Update,
+                (
+                    fit_camera_to_arena,
+                    start_slow_motion,
+                    update_camera_effects.run_if(not(simulating)),
+                )
+                    .chain(),
+            )
+            .add_observer(shake_on_player_hit)
+            .add_observer(punch_on_star_collected);
+    }
+}
+
+#[derive(Component)]
+pub struct CameraEffects {
+    pub base: Vec3,
+    trauma: f32,
+    punch: f32,
+    focus: Option<Vec3>,
+    slow_motion: bool,
+    zoom: f32,
+}
+
+impl CameraEffects {
+    pub fn new(base: Vec3) -> Self {
+        Self {
+            base,
+            trauma: 0.0,
+            punch: 0.0,
+            focus: None,
+            slow_motion: false,
+            zoom: 0.0,
+        }
+    }
+}
+
+fn fit_camera_to_arena(
+    arena: Res<Arena>,
+    settings: Res<Settings>,
+    window_query: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut camera_query: Query<(&mut Camera, &mut Projection, &mut CameraEffects)>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    if !arena.is_changed() && !settings.is_changed() && !window.is_changed() {
+        return;
+    }
+
+    for (mut camera, mut projection, mut effects) in camera_query.iter_mut() {
+        effects.base = arena.center().with_z(effects.base.z);
+
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scaling_mode = ScalingMode::AutoMin {
+                min_width: arena.width,
+                min_height: arena.height,
+            };
+        }
+
+        camera.viewport = match settings.camera_scaling {
+            CameraScaling::Letterbox => letterbox_viewport(&arena, &window),
+            CameraScaling::Fit => None,
+        };
+    }
+}
+
+fn letterbox_viewport(arena: &Arena, window: &Window) -> Option<Viewport> {
+    let window_size = window.physical_size().as_vec2();
+    if window_size.x <= 0.0 || window_size.y <= 0.0 {
+        return None;
+    }
+
+    let scale = (window_size.x / arena.width).min(window_size.y / arena.height);
+    let size = (arena.size() * scale).round();
+    let position = ((window_size - size) / 2.0).floor();
+
+    Some(Viewport {
+        physical_position: position.as_uvec2(),
+        physical_size: size.as_uvec2().max(UVec2::ONE),
+        ..default()
+    })
+}
+
+fn shake_on_player_hit(
+    trigger: Trigger<PlayerHitEvent>,
+    mut camera_query: Query<&mut CameraEffects>,
+    rollback_frame: Option<Res<RollbackFrame>>,
+) {
+    if rollback_frame.is_some_and(|frame| frame.replaying) {
+        return;
+    }
+
+    for mut effects in camera_query.iter_mut() {
+        effects.trauma = (effects.trauma + HIT_TRAUMA).min(1.0);
+        effects.focus = Some(trigger.event().position);
+    }
+}
+
+fn punch_on_star_collected(
+    _trigger: Trigger<CollectStarEvent>,
+    mut camera_query: Query<&mut CameraEffects>,
+    rollback_frame: Option<Res<RollbackFrame>>,
+) {
+    if rollback_frame.is_some_and(|frame| frame.replaying) {
+        return;
+    }
+
+    for mut effects in camera_query.iter_mut() {
+        effects.punch = 1.0;
+    }
+}
+
+fn start_slow_motion(
+    mut event_reader: EventReader<GameOver>,
+    mut camera_query: Query<&mut CameraEffects>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    for _game_over_event in event_reader.read() {
+        time.set_relative_speed(SLOW_MOTION_SPEED);
+        for mut effects in camera_query.iter_mut() {
+            effects.slow_motion = true;
+        }
+    }
+}
+
+fn reset_camera_effects(mut camera_query: Query<&mut CameraEffects>, mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(1.0);
+    for mut effects in camera_query.iter_mut() {
+        *effects = CameraEffects::new(effects.base);
+    }
+}
+
+fn update_camera_effects(
+    mut camera_query: Query<(&mut Transform, &mut Projection, &mut CameraEffects)>,
+    settings: Res<Settings>,
+    time: Res<Time<Real>>,
+) {
+    for (mut transform, mut projection, mut effects) in camera_query.iter_mut() {
+        let delta = time.delta_secs();
+        effects.trauma = (effects.trauma - TRAUMA_DECAY * delta).max(0.0);
+        effects.punch = (effects.punch - PUNCH_DECAY * delta).max(0.0);
+        if effects.slow_motion {
+            effects.zoom = (effects.zoom + GAME_OVER_ZOOM_SPEED * delta).min(1.0);
+        }
+
+        let mut translation = effects.base;
+        let mut rotation = Quat::IDENTITY;
+        let mut scale = 1.0;
+
+        if !settings.reduce_motion {
+            let shake = effects.trauma * effects.trauma;
+            let zoom = effects.zoom * (2.0 - effects.zoom);
+            let focus = effects.focus.unwrap_or(effects.base).with_z(effects.base.z);
+
+            translation = effects.base.lerp(focus, zoom * 0.5);
+            translation += Vec3::new(signed_random(), signed_random(), 0.0) * MAX_SHAKE_OFFSET * shake;
+            rotation = Quat::from_rotation_z(MAX_SHAKE_ANGLE * shake * signed_random());
+            scale -= PUNCH_ZOOM * effects.punch + GAME_OVER_ZOOM * zoom;
+        }
+
+        transform.translation = translation;
+        transform.rotation = rotation;
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scale = scale;
+        }
+    }
+}
+
+fn signed_random() -> f32 {
+    random::<f32>() * 2.0 - 1.0
+}
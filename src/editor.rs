@@ -0,0 +1,895 @@
+use crate::app_state::AppState;
+use crate::camera::CameraEffects;
+use crate::game::{
+    Arena, ArenaBoundary, EnemyArchetype, EnemyDef, GameMode, Level, ObstacleDef, ObstacleShape, SelectedLevel,
+    StarRegion, WinCondition, paused,
+};
+use crate::ui::{small_button_bundle, text_bundle};
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::PrimaryWindow;
+use ron::ser::PrettyConfig;
+use std::f32::consts::{PI, TAU};
+use std::fs;
+use std::path::Path;
+
+const SAVE_DIR: &str = "save";
+const CUSTOM_LEVEL_FILE: &str = "save/custom.level.ron";
+const PANEL_WIDTH: f32 = 300.0;
+const MIN_DRAG: f32 = 8.0;
+const SIZE_STEP: f32 = 10.0;
+const ARENA_STEP: f32 = 80.0;
+const ANGLE_STEP: f32 = PI / 12.0;
+const DEFAULT_RECTANGLE: Vec2 = Vec2::new(120.0, 40.0);
+const DEFAULT_RADIUS: f32 = 40.0;
+const DEFAULT_ZONE_SIZE: f32 = 160.0;
+const PLAYER_MARKER_RADIUS: f32 = 32.0;
+const ARENA_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const PLAYER_COLOR: Color = Color::srgb(0.42, 0.82, 0.82);
+const OBSTACLE_COLOR: Color = Color::srgb(0.5, 0.7, 0.7);
+const STAR_ZONE_COLOR: Color = Color::srgb(0.75, 0.95, 0.33);
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+const PREVIEW_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.5);
+const PANEL_COLOR: Color = Color::srgba(0.05, 0.1, 0.1, 0.9);
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EditorLevel(load_custom_level().unwrap_or_else(new_level)))
+            .init_resource::<EditorState>()
+            .add_event::<StartPlaytest>()
+            .add_systems(OnEnter(AppState::Editor), spawn_editor_panel)
+            .add_systems(OnExit(AppState::Editor), despawn_editor_panel)
+            .add_systems(
+                Update,
+                (
+                    interact_with_tool_buttons,
+                    interact_with_property_buttons,
+                    interact_with_delete_button,
+                    interact_with_action_buttons,
+                    editor_shortcuts,
+                    start_playtest,
+                    edit_with_mouse,
+                    sync_editor_arena,
+                    refresh_selection_panel,
+                    update_panel_text,
+                    draw_editor_level,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Editor)),
+            )
+            .add_systems(
+                Update,
+                return_to_editor.run_if(paused.or(in_state(AppState::GameOver)).or(in_state(AppState::Victory))),
+            );
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct EditorLevel(Level);
+
+#[derive(Resource, Default)]
+struct EditorState {
+    tool: Tool,
+    selection: Option<Selection>,
+    drag_start: Option<Vec2>,
+    grab_offset: Option<Vec2>,
+    status: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum Tool {
+    #[default]
+    Select,
+    Rectangle,
+    Circle,
+    Enemy,
+    StarZone,
+}
+
+impl Tool {
+    const ALL: [Tool; 5] = [Tool::Select, Tool::Rectangle, Tool::Circle, Tool::Enemy, Tool::StarZone];
+
+    fn label(self) -> &'static str {
+        match self {
+            Tool::Select => "Select",
+            Tool::Rectangle => "Rect",
+            Tool::Circle => "Circle",
+            Tool::Enemy => "Enemy",
+            Tool::StarZone => "Stars",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Selection {
+    Obstacle(usize),
+    Enemy(usize),
+    StarZone(usize),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Property {
+    Width,
+    Height,
+    Radius,
+    Archetype,
+    Direction,
+    StarCount,
+    StarSpawnTime,
+    EnemySpawnTime,
+    Goal,
+    GoalValue,
+    ArenaWidth,
+    ArenaHeight,
+    Boundary,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Action {
+    Play,
+    Save,
+    Load,
+    New,
+    Menu,
+}
+
+impl Action {
+    const ALL: [Action; 5] = [Action::Play, Action::Save, Action::Load, Action::New, Action::Menu];
+
+    fn label(self) -> &'static str {
+        match self {
+            Action::Play => "Play",
+            Action::Save => "Save",
+            Action::Load => "Load",
+            Action::New => "New",
+            Action::Menu => "Menu",
+        }
+    }
+}
+
+#[derive(Event)]
+struct StartPlaytest;
+
+#[derive(Component)]
+struct EditorPanel;
+
+#[derive(Component)]
+struct SelectionPanel;
+
+#[derive(Component)]
+struct StatusText;
+
+#[derive(Component)]
+struct PropertyText(Property);
+
+#[derive(Component)]
+struct ToolButton(Tool);
+
+#[derive(Component)]
+struct PropertyButton {
+    property: Property,
+    step: f32,
+}
+
+#[derive(Component)]
+struct DeleteButton;
+
+#[derive(Component)]
+struct ActionButton(Action);
+
+fn new_level() -> Level {
+    Level {
+        name: "Custom".to_string(),
+        obstacles: Vec::new(),
+        enemies: Vec::new(),
+        ..default()
+    }
+}
+
+fn load_custom_level() -> Option<Level> {
+    let contents = fs::read_to_string(CUSTOM_LEVEL_FILE).ok()?;
+    let mut level: Level = ron::de::from_str(&contents).ok()?;
+
+    // The editor works with concrete positions, so randomly placed enemies get a spot on a ring.
+    let center = level.arena_size / 2.0;
+    let count = level.enemies.len().max(1) as f32;
+    for (index, enemy) in level.enemies.iter_mut().enumerate() {
+        if enemy.position.is_none() {
+            enemy.position = Some(center + Vec2::from_angle(index as f32 * TAU / count) * center.min_element() / 2.0);
+        }
+    }
+
+    Some(level)
+}
+
+fn save_custom_level(level: &Level) -> Result<(), String> {
+    let contents = ron::ser::to_string_pretty(level, PrettyConfig::default()).map_err(|error| error.to_string())?;
+    fs::create_dir_all(Path::new(SAVE_DIR)).map_err(|error| error.to_string())?;
+    fs::write(CUSTOM_LEVEL_FILE, contents).map_err(|error| error.to_string())
+}
+
+fn spawn_editor_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            EditorPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                width: Val::Px(PANEL_WIDTH),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(12.0)),
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            RelativeCursorPosition::default(),
+        ))
+        .with_children(|parent| {
+            parent.spawn(text_bundle(&asset_server, "Level Editor", 28.0));
+            parent.spawn((StatusText, text_bundle(&asset_server, "", 16.0)));
+
+            parent.spawn(text_bundle(&asset_server, "Tools", 20.0));
+            parent.spawn(wrapping_row()).with_children(|row| {
+                for tool in Tool::ALL {
+                    row.spawn(small_button_bundle(&asset_server, tool.label(), ToolButton(tool)));
+                }
+            });
+
+            parent.spawn(text_bundle(&asset_server, "Selection", 20.0));
+            parent.spawn((
+                SelectionPanel,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+            ));
+
+            parent.spawn(text_bundle(&asset_server, "Level", 20.0));
+            for property in [
+                Property::StarCount,
+                Property::StarSpawnTime,
+                Property::EnemySpawnTime,
+                Property::Goal,
+                Property::GoalValue,
+                Property::ArenaWidth,
+                Property::ArenaHeight,
+                Property::Boundary,
+            ] {
+                spawn_property_row(parent, &asset_server, property);
+            }
+
+            parent.spawn(wrapping_row()).with_children(|row| {
+                for action in Action::ALL {
+                    row.spawn(small_button_bundle(&asset_server, action.label(), ActionButton(action)));
+                }
+            });
+            parent.spawn(text_bundle(
+                &asset_server,
+                "Drag to place, right click to delete. P plays, E returns here.",
+                14.0,
+            ));
+        });
+}
+
+fn wrapping_row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        flex_wrap: FlexWrap::Wrap,
+        column_gap: Val::Px(4.0),
+        row_gap: Val::Px(4.0),
+        ..default()
+    }
+}
+
+fn spawn_property_row(parent: &mut ChildSpawnerCommands, asset_server: &Res<AssetServer>, property: Property) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+                children![(PropertyText(property), text_bundle(asset_server, "", 16.0))],
+            ));
+            row.spawn(small_button_bundle(
+                asset_server,
+                "-",
+                PropertyButton { property, step: -1.0 },
+            ));
+            row.spawn(small_button_bundle(
+                asset_server,
+                "+",
+                PropertyButton { property, step: 1.0 },
+            ));
+        });
+}
+
+fn despawn_editor_panel(mut commands: Commands, query: Query<Entity, With<EditorPanel>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn refresh_selection_panel(
+    mut commands: Commands,
+    panel_query: Query<(Entity, Ref<SelectionPanel>)>,
+    editor: Res<EditorState>,
+    level: Res<EditorLevel>,
+    asset_server: Res<AssetServer>,
+    mut shown_selection: Local<Option<Selection>>,
+) {
+    let Ok((panel, panel_ref)) = panel_query.single() else {
+        return;
+    };
+
+    if !panel_ref.is_added() && *shown_selection == editor.selection {
+        return;
+    }
+    *shown_selection = editor.selection;
+
+    let properties: &[Property] = match editor.selection {
+        Some(Selection::Obstacle(index)) => match level.obstacles[index].shape {
+            ObstacleShape::Rectangle { .. } => &[Property::Width, Property::Height],
+            ObstacleShape::Circle { .. } => &[Property::Radius],
+        },
+        Some(Selection::Enemy(_)) => &[Property::Archetype, Property::Direction],
+        Some(Selection::StarZone(_)) => &[Property::Width, Property::Height],
+        None => &[],
+    };
+
+    commands
+        .entity(panel)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            if editor.selection.is_none() {
+                parent.spawn(text_bundle(&asset_server, "Nothing selected", 16.0));
+                return;
+            }
+
+            for property in properties {
+                spawn_property_row(parent, &asset_server, *property);
+            }
+            parent.spawn(small_button_bundle(&asset_server, "Delete", DeleteButton));
+        });
+}
+
+fn update_panel_text(
+    mut status_query: Query<&mut Text, (With<StatusText>, Without<PropertyText>)>,
+    mut property_query: Query<(&mut Text, &PropertyText)>,
+    editor: Res<EditorState>,
+    level: Res<EditorLevel>,
+) {
+    for mut text in status_query.iter_mut() {
+        **text = format!("Tool: {}  {}", editor.tool.label(), editor.status);
+    }
+
+    for (mut text, property_text) in property_query.iter_mut() {
+        **text = property_label(property_text.0, &level, editor.selection);
+    }
+}
+
+fn property_label(property: Property, level: &Level, selection: Option<Selection>) -> String {
+    match (property, selection) {
+        (Property::Width | Property::Height | Property::Radius, Some(Selection::Obstacle(index))) => {
+            match level.obstacles[index].shape {
+                ObstacleShape::Rectangle { width, .. } if property == Property::Width => {
+                    format!("Width: {}", width)
+                }
+                ObstacleShape::Rectangle { height, .. } => format!("Height: {}", height),
+                ObstacleShape::Circle { radius } => format!("Radius: {}", radius),
+            }
+        }
+        (Property::Width, Some(Selection::StarZone(index))) => {
+            let zone = level.star_regions[index];
+            format!("Width: {}", zone.max.x - zone.min.x)
+        }
+        (Property::Height, Some(Selection::StarZone(index))) => {
+            let zone = level.star_regions[index];
+            format!("Height: {}", zone.max.y - zone.min.y)
+        }
+        (Property::Archetype, Some(Selection::Enemy(index))) => {
+            format!("Type: {:?}", level.enemies[index].archetype)
+        }
+        (Property::Direction, Some(Selection::Enemy(index))) => match level.enemies[index].direction {
+            Some(direction) => format!("Angle: {:.0}", direction.to_angle().to_degrees()),
+            None => "Angle: random".to_string(),
+        },
+        (Property::StarCount, _) => format!("Stars: {}", level.star_count),
+        (Property::StarSpawnTime, _) => format!("Star every: {:.1}s", level.star_spawn_time),
+        (Property::EnemySpawnTime, _) => format!("Enemy every: {:.1}s", level.enemy_spawn_time),
+        (Property::Goal, _) => match level.win_condition {
+            Some(WinCondition::Score(_)) => "Goal: score".to_string(),
+            Some(WinCondition::Survive(_)) => "Goal: survive".to_string(),
+            Some(WinCondition::AllStars) => "Goal: all stars".to_string(),
+            None => "Goal: none".to_string(),
+        },
+        (Property::GoalValue, _) => match level.win_condition {
+            Some(WinCondition::Score(score)) => format!("Target: {}", score),
+            Some(WinCondition::Survive(seconds)) => format!("Target: {}s", seconds),
+            _ => "Target: -".to_string(),
+        },
+        (Property::ArenaWidth, _) => format!("Arena width: {}", level.arena_size.x),
+        (Property::ArenaHeight, _) => format!("Arena height: {}", level.arena_size.y),
+        (Property::Boundary, _) => match level.boundary {
+            ArenaBoundary::Walls => "Edges: walls".to_string(),
+            ArenaBoundary::Wrap => "Edges: wrap".to_string(),
+        },
+        _ => String::new(),
+    }
+}
+
+fn adjust_property(property: Property, step: f32, level: &mut Level, selection: Option<Selection>) {
+    match (property, selection) {
+        (Property::Width | Property::Height | Property::Radius, Some(Selection::Obstacle(index))) => {
+            match &mut level.obstacles[index].shape {
+                ObstacleShape::Rectangle { width, .. } if property == Property::Width => {
+                    *width = (*width + step * SIZE_STEP).max(SIZE_STEP);
+                }
+                ObstacleShape::Rectangle { height, .. } => {
+                    *height = (*height + step * SIZE_STEP).max(SIZE_STEP);
+                }
+                ObstacleShape::Circle { radius } => {
+                    *radius = (*radius + step * SIZE_STEP / 2.0).max(SIZE_STEP / 2.0);
+                }
+            }
+        }
+        (Property::Width, Some(Selection::StarZone(index))) => {
+            let zone = &mut level.star_regions[index];
+            zone.max.x = (zone.max.x + step * SIZE_STEP).max(zone.min.x + SIZE_STEP);
+        }
+        (Property::Height, Some(Selection::StarZone(index))) => {
+            let zone = &mut level.star_regions[index];
+            zone.max.y = (zone.max.y + step * SIZE_STEP).max(zone.min.y + SIZE_STEP);
+        }
+        (Property::Archetype, Some(Selection::Enemy(index))) => {
+            let enemy = &mut level.enemies[index];
+            let count = EnemyArchetype::ALL.len();
+            let index = EnemyArchetype::ALL
+                .iter()
+                .position(|archetype| *archetype == enemy.archetype)
+                .unwrap_or(0);
+            let next = if step > 0.0 { index + 1 } else { index + count - 1 };
+            enemy.archetype = EnemyArchetype::ALL[next % count];
+        }
+        (Property::Direction, Some(Selection::Enemy(index))) => {
+            let enemy = &mut level.enemies[index];
+            let angle = enemy.direction.map_or(0.0, Vec2::to_angle) + step * ANGLE_STEP;
+            enemy.direction = Some(Vec2::from_angle(angle));
+        }
+        (Property::StarCount, _) => {
+            level.star_count = level.star_count.saturating_add_signed(step as isize);
+        }
+        (Property::StarSpawnTime, _) => {
+            level.star_spawn_time = (level.star_spawn_time + step * 0.5).max(0.0);
+        }
+        (Property::EnemySpawnTime, _) => {
+            level.enemy_spawn_time = (level.enemy_spawn_time + step * 0.5).max(0.5);
+        }
+        (Property::Goal, _) => {
+            let goals = [
+                None,
+                Some(WinCondition::Score(20)),
+                Some(WinCondition::Survive(30.0)),
+                Some(WinCondition::AllStars),
+            ];
+            let current = match level.win_condition {
+                None => 0,
+                Some(WinCondition::Score(_)) => 1,
+                Some(WinCondition::Survive(_)) => 2,
+                Some(WinCondition::AllStars) => 3,
+            };
+            let next = (current as isize + step as isize).rem_euclid(goals.len() as isize) as usize;
+            level.win_condition = goals[next];
+        }
+        (Property::GoalValue, _) => {
+            level.win_condition = match level.win_condition {
+                Some(WinCondition::Score(score)) => {
+                    Some(WinCondition::Score(score.saturating_add_signed(step as i32 * 5).max(1)))
+                }
+                Some(WinCondition::Survive(seconds)) => Some(WinCondition::Survive((seconds + step * 5.0).max(5.0))),
+                other => other,
+            };
+        }
+        (Property::ArenaWidth, _) => {
+            level.arena_size.x = (level.arena_size.x + step * ARENA_STEP).max(ARENA_STEP * 4.0);
+        }
+        (Property::ArenaHeight, _) => {
+            level.arena_size.y = (level.arena_size.y + step * ARENA_STEP).max(ARENA_STEP * 4.0);
+        }
+        (Property::Boundary, _) => {
+            level.boundary = match level.boundary {
+                ArenaBoundary::Walls => ArenaBoundary::Wrap,
+                ArenaBoundary::Wrap => ArenaBoundary::Walls,
+            };
+        }
+        _ => {}
+    }
+}
+
+fn interact_with_tool_buttons(
+    button_query: Query<(&Interaction, &ToolButton), Changed<Interaction>>,
+    mut editor: ResMut<EditorState>,
+) {
+    for (interaction, tool_button) in button_query.iter() {
+        if *interaction == Interaction::Pressed {
+            editor.tool = tool_button.0;
+        }
+    }
+}
+
+fn interact_with_property_buttons(
+    button_query: Query<(&Interaction, &PropertyButton), Changed<Interaction>>,
+    editor: Res<EditorState>,
+    mut level: ResMut<EditorLevel>,
+) {
+    for (interaction, property_button) in button_query.iter() {
+        if *interaction == Interaction::Pressed {
+            adjust_property(
+                property_button.property,
+                property_button.step,
+                &mut level,
+                editor.selection,
+            );
+        }
+    }
+}
+
+fn interact_with_delete_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<DeleteButton>)>,
+    mut editor: ResMut<EditorState>,
+    mut level: ResMut<EditorLevel>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+        && let Some(selection) = editor.selection.take()
+    {
+        remove(&mut level, selection);
+    }
+}
+
+fn interact_with_action_buttons(
+    button_query: Query<(&Interaction, &ActionButton), Changed<Interaction>>,
+    mut editor: ResMut<EditorState>,
+    mut level: ResMut<EditorLevel>,
+    mut playtest_event_writer: EventWriter<StartPlaytest>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, action_button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action_button.0 {
+            Action::Play => {
+                playtest_event_writer.write(StartPlaytest);
+            }
+            Action::Save => {
+                editor.status = match save_custom_level(&level) {
+                    Ok(()) => "Saved".to_string(),
+                    Err(error) => format!("Save failed: {}", error),
+                };
+            }
+            Action::Load => match load_custom_level() {
+                Some(loaded_level) => {
+                    level.0 = loaded_level;
+                    editor.selection = None;
+                    editor.status = "Loaded".to_string();
+                }
+                None => editor.status = "Nothing to load".to_string(),
+            },
+            Action::New => {
+                level.0 = new_level();
+                editor.selection = None;
+                editor.status = String::new();
+            }
+            Action::Menu => next_app_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+fn editor_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<EditorState>,
+    mut level: ResMut<EditorLevel>,
+    mut playtest_event_writer: EventWriter<StartPlaytest>,
+) {
+    let tool_keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+    ];
+    for (key, tool) in tool_keys.into_iter().zip(Tool::ALL) {
+        if keyboard_input.just_pressed(key) {
+            editor.tool = tool;
+        }
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::Delete, KeyCode::Backspace])
+        && let Some(selection) = editor.selection.take()
+    {
+        remove(&mut level, selection);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        playtest_event_writer.write(StartPlaytest);
+    }
+}
+
+fn start_playtest(
+    mut playtest_event_reader: EventReader<StartPlaytest>,
+    level: Res<EditorLevel>,
+    mut level_assets: ResMut<Assets<Level>>,
+    mut game_mode: ResMut<GameMode>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if playtest_event_reader.read().count() > 0 {
+        *game_mode = GameMode::Classic;
+        *selected_level = SelectedLevel::Custom(level_assets.add(level.0.clone()));
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn return_to_editor(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    selected_level: Res<SelectedLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyE) && matches!(*selected_level, SelectedLevel::Custom(_)) {
+        next_app_state.set(AppState::Editor);
+    }
+}
+
+fn cursor_world_position(
+    window_query: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<CameraEffects>>,
+) -> Option<Vec2> {
+    let window = window_query.single().ok()?;
+    let (camera, camera_transform) = camera_query.single().ok()?;
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+}
+
+fn edit_with_mouse(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraEffects>>,
+    panel_query: Query<&RelativeCursorPosition, With<EditorPanel>>,
+    mut editor: ResMut<EditorState>,
+    mut level: ResMut<EditorLevel>,
+) {
+    let Some(cursor) = cursor_world_position(&window_query, &camera_query) else {
+        return;
+    };
+    let over_panel = panel_query.iter().any(RelativeCursorPosition::mouse_over);
+
+    if mouse_input.just_pressed(MouseButton::Left) && !over_panel {
+        if editor.tool == Tool::Select {
+            editor.selection = pick(&level, cursor);
+            editor.grab_offset = editor
+                .selection
+                .map(|selection| position_of(&level, selection) - cursor);
+        } else {
+            editor.drag_start = Some(cursor);
+        }
+    }
+
+    if mouse_input.pressed(MouseButton::Left)
+        && let (Some(selection), Some(grab_offset)) = (editor.selection, editor.grab_offset)
+    {
+        move_to(&mut level, selection, cursor + grab_offset);
+    }
+
+    if mouse_input.just_released(MouseButton::Left) {
+        editor.grab_offset = None;
+        if let Some(start) = editor.drag_start.take() {
+            editor.selection = place(&mut level, editor.tool, start, cursor);
+        }
+    }
+
+    if mouse_input.just_pressed(MouseButton::Right)
+        && !over_panel
+        && let Some(selection) = pick(&level, cursor)
+    {
+        remove(&mut level, selection);
+        editor.selection = None;
+    }
+}
+
+fn pick(level: &Level, cursor: Vec2) -> Option<Selection> {
+    let enemy = level.enemies.iter().rposition(|enemy| {
+        enemy
+            .position
+            .is_some_and(|position| position.distance(cursor) <= enemy.archetype.size() / 2.0)
+    });
+    if let Some(index) = enemy {
+        return Some(Selection::Enemy(index));
+    }
+
+    let obstacle = level.obstacles.iter().rposition(|obstacle| {
+        let offset = cursor - obstacle.position;
+        match obstacle.shape {
+            ObstacleShape::Rectangle { width, height } => {
+                offset.x.abs() <= width / 2.0 && offset.y.abs() <= height / 2.0
+            }
+            ObstacleShape::Circle { radius } => offset.length() <= radius,
+        }
+    });
+    if let Some(index) = obstacle {
+        return Some(Selection::Obstacle(index));
+    }
+
+    level
+        .star_regions
+        .iter()
+        .rposition(|zone| cursor.cmpge(zone.min).all() && cursor.cmple(zone.max).all())
+        .map(Selection::StarZone)
+}
+
+fn position_of(level: &Level, selection: Selection) -> Vec2 {
+    match selection {
+        Selection::Obstacle(index) => level.obstacles[index].position,
+        Selection::Enemy(index) => level.enemies[index].position.unwrap_or_default(),
+        Selection::StarZone(index) => level.star_regions[index].min,
+    }
+}
+
+fn move_to(level: &mut Level, selection: Selection, position: Vec2) {
+    match selection {
+        Selection::Obstacle(index) => level.obstacles[index].position = position,
+        Selection::Enemy(index) => level.enemies[index].position = Some(position),
+        Selection::StarZone(index) => {
+            let zone = &mut level.star_regions[index];
+            let size = zone.max - zone.min;
+            zone.min = position;
+            zone.max = position + size;
+        }
+    }
+}
+
+fn remove(level: &mut Level, selection: Selection) {
+    match selection {
+        Selection::Obstacle(index) => {
+            level.obstacles.remove(index);
+        }
+        Selection::Enemy(index) => {
+            level.enemies.remove(index);
+        }
+        Selection::StarZone(index) => {
+            level.star_regions.remove(index);
+        }
+    }
+}
+
+fn place(level: &mut Level, tool: Tool, start: Vec2, end: Vec2) -> Option<Selection> {
+    let drag = end - start;
+    let dragged = drag.length() > MIN_DRAG;
+
+    match tool {
+        Tool::Select => None,
+        Tool::Rectangle => {
+            let (position, size) = if dragged {
+                ((start + end) / 2.0, drag.abs().max(Vec2::splat(SIZE_STEP)))
+            } else {
+                (start, DEFAULT_RECTANGLE)
+            };
+            level.obstacles.push(ObstacleDef {
+                position,
+                shape: ObstacleShape::Rectangle {
+                    width: size.x,
+                    height: size.y,
+                },
+            });
+            Some(Selection::Obstacle(level.obstacles.len() - 1))
+        }
+        Tool::Circle => {
+            let radius = if dragged { drag.length() } else { DEFAULT_RADIUS };
+            level.obstacles.push(ObstacleDef {
+                position: start,
+                shape: ObstacleShape::Circle { radius },
+            });
+            Some(Selection::Obstacle(level.obstacles.len() - 1))
+        }
+        Tool::Enemy => {
+            level.enemies.push(EnemyDef {
+                archetype: EnemyArchetype::Basic,
+                position: Some(start),
+                direction: dragged.then(|| drag.normalize()),
+            });
+            Some(Selection::Enemy(level.enemies.len() - 1))
+        }
+        Tool::StarZone => {
+            let (min, max) = if dragged {
+                (start.min(end), start.max(end))
+            } else {
+                (start - DEFAULT_ZONE_SIZE / 2.0, start + DEFAULT_ZONE_SIZE / 2.0)
+            };
+            level.star_regions.push(StarRegion { min, max });
+            Some(Selection::StarZone(level.star_regions.len() - 1))
+        }
+    }
+}
+
+fn sync_editor_arena(level: Res<EditorLevel>, mut arena: ResMut<Arena>) {
+    if arena.size() != level.arena_size {
+        arena.width = level.arena_size.x;
+        arena.height = level.arena_size.y;
+    }
+}
+
+fn draw_editor_level(
+    mut gizmos: Gizmos,
+    level: Res<EditorLevel>,
+    editor: Res<EditorState>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<CameraEffects>>,
+) {
+    let color_for = |selection: Selection, color: Color| {
+        if editor.selection == Some(selection) {
+            SELECTED_COLOR
+        } else {
+            color
+        }
+    };
+
+    gizmos.rect_2d(level.arena_size / 2.0, level.arena_size, ARENA_COLOR);
+    gizmos.circle_2d(level.arena_size / 2.0, PLAYER_MARKER_RADIUS, PLAYER_COLOR);
+
+    for (index, zone) in level.star_regions.iter().enumerate() {
+        let color = color_for(Selection::StarZone(index), STAR_ZONE_COLOR);
+        gizmos.rect_2d((zone.min + zone.max) / 2.0, zone.max - zone.min, color);
+    }
+
+    for (index, obstacle) in level.obstacles.iter().enumerate() {
+        let color = color_for(Selection::Obstacle(index), OBSTACLE_COLOR);
+        match obstacle.shape {
+            ObstacleShape::Rectangle { width, height } => {
+                gizmos.rect_2d(obstacle.position, Vec2::new(width, height), color);
+            }
+            ObstacleShape::Circle { radius } => {
+                gizmos.circle_2d(obstacle.position, radius, color);
+            }
+        }
+    }
+
+    for (index, enemy) in level.enemies.iter().enumerate() {
+        let Some(position) = enemy.position else {
+            continue;
+        };
+        let size = enemy.archetype.size();
+        let color = color_for(Selection::Enemy(index), enemy.archetype.color());
+        gizmos.circle_2d(position, size / 2.0, color);
+        if let Some(direction) = enemy.direction {
+            gizmos.arrow_2d(position, position + direction * size, color);
+        }
+    }
+
+    if let Some(start) = editor.drag_start
+        && let Some(cursor) = cursor_world_position(&window_query, &camera_query)
+    {
+        match editor.tool {
+            Tool::Rectangle | Tool::StarZone => {
+                gizmos.rect_2d((start + cursor) / 2.0, (cursor - start).abs(), PREVIEW_COLOR);
+            }
+            Tool::Circle => {
+                gizmos.circle_2d(start, start.distance(cursor), PREVIEW_COLOR);
+            }
+            Tool::Enemy => {
+                gizmos.arrow_2d(start, cursor, PREVIEW_COLOR);
+            }
+            Tool::Select => {}
+        }
+    }
+}
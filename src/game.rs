@@ -1,31 +1,110 @@
+mod arena;
+mod boss;
+mod campaign;
+mod coop;
+mod daily;
+mod dash;
 mod enemies;
 mod hub;
+mod level;
+mod mode;
+mod movement;
+mod mutators;
+mod obstacles;
+mod particles;
 mod pause_menu;
+mod placement;
 mod player;
+mod power_ups;
+mod projectiles;
+mod rng;
+mod rollback;
 mod score;
 mod stars;
 mod state;
+mod survival;
+mod time_attack;
+mod versus;
+mod victory;
 
+use arena::ArenaPlugin;
+pub use arena::{Arena, ArenaBoundary};
 use bevy::prelude::*;
+use boss::BossPlugin;
+use campaign::CampaignPlugin;
+pub use campaign::CampaignProgress;
+use coop::CoopPlugin;
+pub use coop::Party;
+use daily::DailyPlugin;
+pub use daily::{DailyChallenge, DailyHistory};
+use dash::DashPlugin;
 use enemies::EnemyPlugin;
+pub use enemies::{Enemy, EnemyArchetype, PlayerHitEvent};
 use hub::HubPlugin;
+use level::LevelPlugin;
+pub use level::{EnemyDef, Level, Levels, SelectedLevel, StarRegion, WinCondition};
+use mode::ModePlugin;
+pub use mode::{GameMode, HighScores};
+pub use movement::Collider;
+use movement::MovementPlugin;
+use mutators::MutatorPlugin;
+pub use mutators::{Mutator, SelectedMutators};
+use obstacles::ObstaclePlugin;
+pub use obstacles::{ObstacleDef, ObstacleShape};
+use particles::ParticlePlugin;
 use pause_menu::PauseMenuPlugin;
+use placement::PlacementPlugin;
 use player::PlayerPlugin;
-pub use score::Score;
+pub use player::{Controls, MAX_PLAYERS, PLAYER_COLORS, Player, RemoteInput};
+use power_ups::PowerUpPlugin;
+pub use projectiles::Projectile;
+use projectiles::ProjectilePlugin;
+pub use rng::MatchSeed;
+use rng::RngPlugin;
+use rollback::RollbackPlugin;
+pub use rollback::{GameSnapshot, RollbackFrame, despawn_retired_before, simulating};
 use score::ScorePlugin;
+pub use score::{MatchStats, Score};
 use stars::StarPlugin;
+pub use stars::{CollectStarEvent, Star};
 use state::GameStatePlugin;
+pub use state::paused;
+use survival::SurvivalPlugin;
+use time_attack::TimeAttackPlugin;
+use versus::VersusPlugin;
+use victory::VictoryPlugin;
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(GameStatePlugin)
+        app.add_plugins(ArenaPlugin)
+            .add_plugins(LevelPlugin)
+            .add_plugins(CampaignPlugin)
+            .add_plugins(RngPlugin)
+            .add_plugins(RollbackPlugin)
+            .add_plugins(ModePlugin)
+            .add_plugins(MutatorPlugin)
+            .add_plugins(DailyPlugin)
+            .add_plugins(GameStatePlugin)
             .add_plugins(HubPlugin)
             .add_plugins(PauseMenuPlugin)
             .add_plugins(ScorePlugin)
+            .add_plugins(TimeAttackPlugin)
+            .add_plugins(SurvivalPlugin)
+            .add_plugins(VersusPlugin)
+            .add_plugins(ObstaclePlugin)
+            .add_plugins(MovementPlugin)
+            .add_plugins(CoopPlugin)
+            .add_plugins(PlacementPlugin)
             .add_plugins(PlayerPlugin)
+            .add_plugins(DashPlugin)
             .add_plugins(StarPlugin)
-            .add_plugins(EnemyPlugin);
+            .add_plugins(EnemyPlugin)
+            .add_plugins(BossPlugin)
+            .add_plugins(ProjectilePlugin)
+            .add_plugins(PowerUpPlugin)
+            .add_plugins(ParticlePlugin)
+            .add_plugins(VictoryPlugin);
     }
 }
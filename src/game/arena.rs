@@ -0,0 +1,232 @@
+use super::state::running;
+use crate::app_state::AppState;
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use serde::{Deserialize, Serialize};
+
+const ARENA_WIDTH: f32 = 1280.0;
+const ARENA_HEIGHT: f32 = 720.0;
+const ARENA_FLOOR_Z: f32 = -1.0;
+const ARENA_FLOOR_COLOR: Color = Color::srgb_u8(18, 50, 50);
+
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Arena>()
+            .add_systems(OnEnter(AppState::Game), spawn_arena_floor)
+            .add_systems(OnExit(AppState::Game), despawn_arena_floor)
+            .add_systems(Update, resize_arena_floor)
+            .add_systems(
+                Update,
+                (wrap_positions, spawn_ghosts, update_ghosts)
+                    .chain()
+                    .run_if(running)
+                    .run_if(wrapping),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ArenaBoundary {
+    #[default]
+    Walls,
+    Wrap,
+}
+
+#[derive(Resource)]
+pub struct Arena {
+    pub width: f32,
+    pub height: f32,
+    pub boundary: ArenaBoundary,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self {
+            width: ARENA_WIDTH,
+            height: ARENA_HEIGHT,
+            boundary: ArenaBoundary::Walls,
+        }
+    }
+}
+
+impl Arena {
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+
+    pub fn center(&self) -> Vec3 {
+        Vec3::new(self.width / 2.0, self.height / 2.0, 0.0)
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.boundary == ArenaBoundary::Wrap
+    }
+
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let delta = to - from;
+        if self.wraps() {
+            delta - self.size() * (delta / self.size()).round()
+        } else {
+            delta
+        }
+    }
+
+    pub fn distance(&self, from: Vec3, to: Vec3) -> f32 {
+        self.delta(from.truncate(), to.truncate()).length()
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Wrappable;
+
+#[derive(Component)]
+struct Ghosted;
+
+#[derive(Component)]
+struct Ghost {
+    axes: Vec2,
+}
+
+pub fn wrapping(arena: Res<Arena>) -> bool {
+    arena.wraps()
+}
+
+#[derive(Component)]
+struct ArenaFloor;
+
+fn spawn_arena_floor(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial2d(materials.add(ARENA_FLOOR_COLOR)),
+        arena_floor_transform(&arena),
+        ArenaFloor,
+    ));
+}
+
+fn despawn_arena_floor(mut commands: Commands, floor_query: Query<Entity, With<ArenaFloor>>) {
+    for entity in floor_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn resize_arena_floor(arena: Res<Arena>, mut floor_query: Query<&mut Transform, With<ArenaFloor>>) {
+    if arena.is_changed() {
+        for mut transform in floor_query.iter_mut() {
+            *transform = arena_floor_transform(&arena);
+        }
+    }
+}
+
+fn arena_floor_transform(arena: &Arena) -> Transform {
+    Transform::from_translation(arena.center().with_z(ARENA_FLOOR_Z)).with_scale(arena.size().extend(1.0))
+}
+
+fn wrap_positions(mut query: Query<&mut Transform, With<Wrappable>>, arena: Res<Arena>) {
+    for mut transform in query.iter_mut() {
+        let position = transform.translation.truncate();
+        let wrapped = position.rem_euclid(arena.size());
+        if wrapped != position {
+            transform.translation = wrapped.extend(transform.translation.z);
+        }
+    }
+}
+
+type GhostSourceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Mesh2d, &'static MeshMaterial2d<ColorMaterial>),
+    (With<Wrappable>, Without<Ghosted>),
+>;
+
+fn spawn_ghosts(mut commands: Commands, query: GhostSourceQuery) {
+    for (entity, mesh, material) in query.iter() {
+        commands.entity(entity).insert(Ghosted).with_children(|parent| {
+            for axes in [Vec2::X, Vec2::Y, Vec2::ONE] {
+                parent.spawn((
+                    Ghost { axes },
+                    mesh.clone(),
+                    material.clone(),
+                    Transform::default(),
+                    Visibility::Hidden,
+                ));
+            }
+        });
+    }
+}
+
+fn update_ghosts(
+    parent_query: Query<(&Transform, &Aabb, &Children), With<Ghosted>>,
+    mut ghost_query: Query<(&Ghost, &mut Transform, &mut Visibility), Without<Ghosted>>,
+    arena: Res<Arena>,
+) {
+    for (transform, aabb, children) in parent_query.iter() {
+        let position = transform.translation.truncate();
+        let reach = aabb.half_extents.truncate() * transform.scale.truncate();
+        let near_edge = position.cmplt(reach) | position.cmpgt(arena.size() - reach);
+        let offset = Vec2::select(position.cmplt(arena.size() / 2.0), arena.size(), -arena.size());
+
+        for child in children.iter() {
+            let Ok((ghost, mut ghost_transform, mut visibility)) = ghost_query.get_mut(child) else {
+                continue;
+            };
+
+            let straddling = (ghost.axes.x == 0.0 || near_edge.x) && (ghost.axes.y == 0.0 || near_edge.y);
+            *visibility = if straddling {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            ghost_transform.translation = (offset * ghost.axes / transform.scale.truncate()).extend(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena(boundary: ArenaBoundary) -> Arena {
+        Arena {
+            width: 100.0,
+            height: 50.0,
+            boundary,
+        }
+    }
+
+    #[test]
+    fn walled_delta_is_the_straight_offset() {
+        let arena = arena(ArenaBoundary::Walls);
+        assert_eq!(
+            arena.delta(Vec2::new(5.0, 5.0), Vec2::new(95.0, 45.0)),
+            Vec2::new(90.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn wrapped_delta_takes_the_shorter_way_across_the_seam() {
+        let arena = arena(ArenaBoundary::Wrap);
+        assert_eq!(
+            arena.delta(Vec2::new(5.0, 5.0), Vec2::new(95.0, 45.0)),
+            Vec2::new(-10.0, -10.0)
+        );
+        assert_eq!(
+            arena.delta(Vec2::new(95.0, 45.0), Vec2::new(5.0, 5.0)),
+            Vec2::new(10.0, 10.0)
+        );
+        assert_eq!(
+            arena.delta(Vec2::new(20.0, 10.0), Vec2::new(40.0, 20.0)),
+            Vec2::new(20.0, 10.0)
+        );
+        assert_eq!(
+            arena.distance(Vec3::new(1.0, 25.0, 0.0), Vec3::new(99.0, 25.0, 0.0)),
+            2.0
+        );
+    }
+}
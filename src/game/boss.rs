@@ -0,0 +1,422 @@
+use super::arena::Arena;
+use super::enemies::{Enemy, EnemyArchetype, spawn_enemy};
+use super::level::{EnemyDef, SelectedLevel};
+use super::movement::{Collider, Velocity};
+use super::placement::PlacementParams;
+use super::player::{Player, nearest_player};
+use super::power_ups::{PowerUpAssets, PowerUpKind, spawn_power_up};
+use super::projectiles::Shooter;
+use super::rng::GameRng;
+use super::rollback::retire;
+use super::score::Score;
+use super::stars::CollectStarEvent;
+use super::state::running;
+use super::survival::survival;
+use crate::app_state::AppState;
+use crate::sound::SpatialSounds;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+const BOSS_SIZE: f32 = 140.0;
+const BOSS_SPEED: f32 = 90.0;
+const BOSS_CHARGE_SPEED: f32 = 650.0;
+const BOSS_CHARGE_TIME: f32 = 0.9;
+const BOSS_TELEGRAPH_TIME: f32 = 0.8;
+const BOSS_BASE_HEALTH: u32 = 6;
+const BOSS_HEALTH_STEP: u32 = 3;
+const BOSS_FIRST_THRESHOLD: u32 = 20;
+const BOSS_THRESHOLD_STEP: u32 = 40;
+const BOSS_STAR_DAMAGE_RADIUS: f32 = 260.0;
+const BOSS_FIRST_ATTACK_DELAY: f32 = 4.0;
+const BOSS_FIRE_TIME: f32 = 1.6;
+const BOSS_REWARD_SPACING: f32 = 48.0;
+const BOSS_COLOR: Color = Color::srgb(0.55, 0.1, 0.35);
+const BOSS_TELEGRAPH_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+const MINION_LIFETIME: f32 = 6.0;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossSpawner>()
+            .add_systems(OnEnter(AppState::Game), reset_boss_spawner)
+            .add_systems(
+                Update,
+                (
+                    spawn_boss_at_threshold.run_if(endless).run_if(not(survival)),
+                    update_boss_actions,
+                    draw_boss_telegraph,
+                    expire_minions,
+                )
+                    .run_if(running),
+            )
+            .add_observer(damage_boss_near_star)
+            .add_observer(damage_boss)
+            .add_observer(drop_boss_rewards)
+            .add_observer(spawn_minion_burst)
+            .add_observer(play_boss_defeat_sound);
+    }
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct BossSpawner {
+    next_threshold: u32,
+    defeated: u32,
+    active: bool,
+}
+
+#[derive(Component, Clone)]
+pub struct Boss {
+    pub health: u32,
+    pub max_health: u32,
+    attack_timer: Timer,
+    attacks: u32,
+    action: BossAction,
+}
+
+impl Boss {
+    fn phase(&self) -> u32 {
+        1 + ((self.max_health - self.health) * 3 / self.max_health).min(2)
+    }
+
+    fn attack_interval(&self) -> f32 {
+        match self.phase() {
+            1 => 4.0,
+            2 => 3.0,
+            _ => 2.0,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum BossAction {
+    Roaming,
+    Telegraphing {
+        attack: BossAttack,
+        direction: Vec2,
+        heading: Vec2,
+        timer: Timer,
+    },
+    Charging {
+        timer: Timer,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum BossAttack {
+    Burst,
+    Charge,
+}
+
+#[derive(Component, Clone)]
+pub struct Minion {
+    lifetime: Timer,
+}
+
+#[derive(Event)]
+pub struct BossHitEvent {
+    pub boss: Entity,
+    pub damage: u32,
+}
+
+#[derive(Event)]
+pub struct BossDefeatedEvent {
+    pub position: Vec3,
+}
+
+#[derive(Event)]
+struct MinionBurstEvent {
+    position: Vec2,
+    count: usize,
+}
+
+fn endless(selected_level: Res<SelectedLevel>) -> bool {
+    matches!(*selected_level, SelectedLevel::Endless)
+}
+
+fn reset_boss_spawner(mut boss_spawner: ResMut<BossSpawner>) {
+    *boss_spawner = BossSpawner {
+        next_threshold: BOSS_FIRST_THRESHOLD,
+        ..default()
+    };
+}
+
+fn spawn_boss_at_threshold(
+    mut commands: Commands,
+    score: Res<Score>,
+    mut boss_spawner: ResMut<BossSpawner>,
+    player_query: Query<&Transform, With<Player>>,
+    arena: Res<Arena>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if boss_spawner.active || score.value < boss_spawner.next_threshold {
+        return;
+    }
+
+    let players: Vec<Vec2> = player_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    if players.is_empty() {
+        return;
+    }
+
+    // Enter on the far side of the arena so the players have time to react.
+    let centroid = players.iter().sum::<Vec2>() / players.len() as f32;
+    let position = (arena.size() - centroid).clamp(Vec2::splat(BOSS_SIZE), arena.size() - BOSS_SIZE);
+    let direction = (arena.center().truncate() - position)
+        .try_normalize()
+        .unwrap_or(Vec2::X);
+    let health = BOSS_BASE_HEALTH + BOSS_HEALTH_STEP * boss_spawner.defeated;
+
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(BOSS_SIZE / 2.0))),
+        MeshMaterial2d(materials.add(BOSS_COLOR)),
+        Transform::from_translation(position.extend(0.0)),
+        Enemy { speed: BOSS_SPEED },
+        Velocity(direction * BOSS_SPEED),
+        Collider {
+            radius: BOSS_SIZE / 2.0,
+        },
+        Boss {
+            health,
+            max_health: health,
+            attack_timer: Timer::from_seconds(BOSS_FIRST_ATTACK_DELAY, TimerMode::Once),
+            attacks: 0,
+            action: BossAction::Roaming,
+        },
+        Shooter::new(BOSS_FIRE_TIME),
+    ));
+    boss_spawner.active = true;
+}
+
+fn update_boss_actions(
+    mut commands: Commands,
+    mut boss_query: Query<(&Transform, &mut Enemy, &mut Velocity, &mut Boss)>,
+    player_query: Query<&Transform, (With<Player>, Without<Boss>)>,
+    arena: Res<Arena>,
+    time: Res<Time>,
+) {
+    for (transform, mut enemy, mut velocity, mut boss) in boss_query.iter_mut() {
+        let boss = &mut *boss;
+        let position = transform.translation.truncate();
+        let phase = boss.phase();
+
+        match &mut boss.action {
+            BossAction::Roaming => {
+                if !boss.attack_timer.tick(time.delta()).finished() {
+                    continue;
+                }
+
+                let attack = if phase > 1 && boss.attacks % 2 == 1 {
+                    BossAttack::Charge
+                } else {
+                    BossAttack::Burst
+                };
+                let heading = velocity.try_normalize().unwrap_or(Vec2::X);
+                let direction = nearest_player(
+                    &arena,
+                    transform.translation,
+                    player_query.iter().map(|player| (player, ())),
+                )
+                .and_then(|(player, _)| arena.delta(position, player.translation.truncate()).try_normalize())
+                .unwrap_or(heading);
+
+                enemy.speed = 0.0;
+                boss.attacks += 1;
+                boss.action = BossAction::Telegraphing {
+                    attack,
+                    direction,
+                    heading,
+                    timer: Timer::from_seconds(BOSS_TELEGRAPH_TIME, TimerMode::Once),
+                };
+            }
+            BossAction::Telegraphing {
+                attack,
+                direction,
+                heading,
+                timer,
+            } => {
+                if !timer.tick(time.delta()).finished() {
+                    continue;
+                }
+
+                match *attack {
+                    BossAttack::Burst => {
+                        commands.trigger(MinionBurstEvent {
+                            position,
+                            count: 4 + 2 * phase as usize,
+                        });
+                        velocity.0 = *heading;
+                        enemy.speed = BOSS_SPEED * phase as f32;
+                        boss.action = BossAction::Roaming;
+                    }
+                    BossAttack::Charge => {
+                        velocity.0 = *direction;
+                        enemy.speed = BOSS_CHARGE_SPEED;
+                        boss.action = BossAction::Charging {
+                            timer: Timer::from_seconds(BOSS_CHARGE_TIME, TimerMode::Once),
+                        };
+                    }
+                }
+            }
+            BossAction::Charging { timer } => {
+                if !timer.tick(time.delta()).finished() {
+                    continue;
+                }
+
+                enemy.speed = BOSS_SPEED * phase as f32;
+                boss.action = BossAction::Roaming;
+            }
+        }
+
+        if matches!(boss.action, BossAction::Roaming) && boss.attack_timer.finished() {
+            let interval = boss.attack_interval();
+            boss.attack_timer = Timer::from_seconds(interval, TimerMode::Once);
+        }
+    }
+}
+
+fn draw_boss_telegraph(mut gizmos: Gizmos, boss_query: Query<(&Transform, &Boss)>) {
+    for (transform, boss) in boss_query.iter() {
+        let BossAction::Telegraphing {
+            attack,
+            direction,
+            timer,
+            ..
+        } = &boss.action
+        else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+        let progress = timer.fraction();
+        match attack {
+            BossAttack::Burst => {
+                gizmos.circle_2d(position, BOSS_SIZE / 2.0 + progress * BOSS_SIZE, BOSS_TELEGRAPH_COLOR);
+            }
+            BossAttack::Charge => {
+                gizmos.arrow_2d(
+                    position,
+                    position + *direction * (BOSS_SIZE + progress * BOSS_SIZE * 2.0),
+                    BOSS_TELEGRAPH_COLOR,
+                );
+            }
+        }
+    }
+}
+
+fn spawn_minion_burst(
+    trigger: Trigger<MinionBurstEvent>,
+    mut commands: Commands,
+    placement: PlacementParams,
+    mut game_rng: ResMut<GameRng>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let event = trigger.event();
+    let mut placement = placement.placement();
+
+    for index in 0..event.count {
+        let direction = Vec2::from_angle(index as f32 * TAU / event.count as f32);
+        let minion = EnemyDef {
+            archetype: EnemyArchetype::Fast,
+            position: Some(event.position + direction * BOSS_SIZE / 2.0),
+            direction: Some(direction),
+        };
+        let Some(entity) = spawn_enemy(
+            &mut commands,
+            &mut placement,
+            &minion,
+            &mut game_rng,
+            &mut meshes,
+            &mut materials,
+        ) else {
+            continue;
+        };
+        commands.entity(entity).insert(Minion {
+            lifetime: Timer::from_seconds(MINION_LIFETIME, TimerMode::Once),
+        });
+    }
+}
+
+fn expire_minions(mut commands: Commands, mut minion_query: Query<(Entity, &mut Minion)>, time: Res<Time>) {
+    for (entity, mut minion) in minion_query.iter_mut() {
+        if minion.lifetime.tick(time.delta()).finished() {
+            retire(&mut commands, entity);
+        }
+    }
+}
+
+fn damage_boss_near_star(
+    trigger: Trigger<CollectStarEvent>,
+    mut commands: Commands,
+    boss_query: Query<(Entity, &Transform), With<Boss>>,
+    arena: Res<Arena>,
+) {
+    let star_position = trigger.event().position;
+
+    for (entity, transform) in boss_query.iter() {
+        if arena.distance(star_position, transform.translation) < BOSS_STAR_DAMAGE_RADIUS {
+            commands.trigger(BossHitEvent {
+                boss: entity,
+                damage: 1,
+            });
+        }
+    }
+}
+
+fn damage_boss(
+    trigger: Trigger<BossHitEvent>,
+    mut commands: Commands,
+    mut boss_query: Query<(&Transform, &mut Boss)>,
+    mut boss_spawner: ResMut<BossSpawner>,
+    score: Res<Score>,
+) {
+    let event = trigger.event();
+    let Ok((transform, mut boss)) = boss_query.get_mut(event.boss) else {
+        return;
+    };
+
+    boss.health = boss.health.saturating_sub(event.damage);
+    if boss.health > 0 {
+        return;
+    }
+
+    retire(&mut commands, event.boss);
+    commands.trigger(BossDefeatedEvent {
+        position: transform.translation,
+    });
+
+    boss_spawner.active = false;
+    boss_spawner.defeated += 1;
+    boss_spawner.next_threshold = score.value + BOSS_THRESHOLD_STEP;
+}
+
+fn drop_boss_rewards(
+    trigger: Trigger<BossDefeatedEvent>,
+    mut commands: Commands,
+    placement: PlacementParams,
+    power_up_assets: Res<PowerUpAssets>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let boss_position = trigger.event().position.truncate();
+    let mut placement = placement.placement();
+
+    for (kind, side) in [(PowerUpKind::Shield, -1.0), (PowerUpKind::Bonus, 1.0)] {
+        let position = boss_position + Vec2::X * side * BOSS_REWARD_SPACING;
+        spawn_power_up(
+            &mut commands,
+            &power_up_assets,
+            &mut placement,
+            kind,
+            position,
+            &mut game_rng,
+        );
+    }
+}
+
+fn play_boss_defeat_sound(trigger: Trigger<BossDefeatedEvent>, mut commands: Commands, sounds: SpatialSounds) {
+    sounds.play(&mut commands, "audio/explosionCrunch_000.ogg", trigger.event().position);
+}
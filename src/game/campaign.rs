@@ -0,0 +1,70 @@
+use super::level::SelectedLevel;
+use crate::app_state::Victory;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const SAVE_DIR: &str = "save";
+const PROGRESS_FILE: &str = "save/campaign.ron";
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CampaignProgress::load())
+            .add_systems(Update, unlock_next_level);
+    }
+}
+
+#[derive(Resource, Debug, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    pub unlocked: usize,
+}
+
+impl Default for CampaignProgress {
+    fn default() -> Self {
+        Self { unlocked: 1 }
+    }
+}
+
+impl CampaignProgress {
+    fn load() -> Self {
+        fs::read_to_string(PROGRESS_FILE)
+            .ok()
+            .and_then(|contents| ron::de::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = fs::create_dir_all(Path::new(SAVE_DIR))
+            .map_err(|error| error.to_string())
+            .and_then(|_| ron::ser::to_string(self).map_err(|error| error.to_string()))
+            .and_then(|contents| fs::write(PROGRESS_FILE, contents).map_err(|error| error.to_string()));
+
+        if let Err(error) = result {
+            warn!("Failed to save campaign progress: {}", error);
+        }
+    }
+
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index < self.unlocked
+    }
+}
+
+fn unlock_next_level(
+    mut event_reader: EventReader<Victory>,
+    selected_level: Res<SelectedLevel>,
+    mut progress: ResMut<CampaignProgress>,
+) {
+    if event_reader.read().count() == 0 {
+        return;
+    }
+
+    if let SelectedLevel::Campaign(index) = *selected_level
+        && progress.unlocked < index + 2
+    {
+        progress.unlocked = index + 2;
+        progress.save();
+    }
+}
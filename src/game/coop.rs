@@ -0,0 +1,133 @@
+use super::arena::Arena;
+use super::enemies::PlayerHitEvent;
+use super::mode::GameMode;
+use super::movement::{Collider, MovementSet};
+use super::player::{Controls, PLAYER_COLORS, PLAYER_SIZE, Player, Shielded, spawn_player};
+use super::rollback::retire;
+use super::state::running;
+use crate::app_state::AppState;
+use crate::sound::SpatialSounds;
+use bevy::prelude::*;
+
+const REVIVE_RADIUS: f32 = 24.0;
+const REVIVE_SHIELD_TIME: f32 = 2.0;
+
+pub struct CoopPlugin;
+
+impl Plugin for CoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Party>()
+            .add_systems(OnExit(AppState::Game), despawn_downed_players)
+            .add_systems(
+                Update,
+                (revive_partners.after(MovementSet), draw_downed_players).run_if(running),
+            )
+            .add_observer(leave_downed_player);
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Party {
+    #[default]
+    Solo,
+    Coop,
+}
+
+impl Party {
+    pub const ALL: [Party; 2] = [Party::Solo, Party::Coop];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Party::Solo => "1 Player",
+            Party::Coop => "2 Players Co-op",
+        }
+    }
+
+    pub fn player_count(self, game_mode: GameMode) -> usize {
+        match (self, game_mode) {
+            (_, GameMode::Versus) | (Party::Coop, _) => 2,
+            (Party::Solo, _) => 1,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct DownedPlayer {
+    index: usize,
+    controls: Controls,
+}
+
+fn leave_downed_player(
+    trigger: Trigger<PlayerHitEvent>,
+    mut commands: Commands,
+    party: Res<Party>,
+    game_mode: Res<GameMode>,
+    player_query: Query<(&Player, &Controls)>,
+) {
+    if *party != Party::Coop || matches!(*game_mode, GameMode::TimeAttack | GameMode::Versus) {
+        return;
+    }
+
+    let event = trigger.event();
+    let Ok((player, controls)) = player_query.get(event.player) else {
+        return;
+    };
+
+    commands.spawn((
+        Transform::from_translation(event.position),
+        DownedPlayer {
+            index: player.index,
+            controls: *controls,
+        },
+    ));
+}
+
+fn revive_partners(
+    mut commands: Commands,
+    downed_query: Query<(Entity, &Transform, &DownedPlayer)>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    arena: Res<Arena>,
+    sounds: SpatialSounds,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, transform, downed) in downed_query.iter() {
+        let touched = player_query.iter().any(|(player_transform, collider)| {
+            arena.distance(player_transform.translation, transform.translation) < collider.radius + REVIVE_RADIUS
+        });
+        if !touched {
+            continue;
+        }
+
+        retire(&mut commands, entity);
+        let player = spawn_player(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            downed.index,
+            downed.controls,
+            transform.translation.truncate(),
+        );
+        commands.entity(player).insert(Shielded {
+            timer: Timer::from_seconds(REVIVE_SHIELD_TIME, TimerMode::Once),
+        });
+        sounds.play(&mut commands, "audio/laserLarge_000.ogg", transform.translation);
+    }
+}
+
+fn draw_downed_players(mut gizmos: Gizmos, downed_query: Query<(&Transform, &DownedPlayer)>, time: Res<Time>) {
+    let pulse = (time.elapsed_secs() * 4.0).sin() * 0.5 + 0.5;
+
+    for (transform, downed) in downed_query.iter() {
+        let position = transform.translation.truncate();
+        let color = PLAYER_COLORS[downed.index];
+        gizmos.circle_2d(position, PLAYER_SIZE / 2.0, color.with_alpha(0.4 + 0.4 * pulse));
+        gizmos.cross_2d(position, PLAYER_SIZE / 4.0, color);
+    }
+}
+
+fn despawn_downed_players(mut commands: Commands, downed_query: Query<Entity, With<DownedPlayer>>) {
+    for entity in downed_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,190 @@
+use super::level::LevelSet;
+use super::mode::GameMode;
+use super::mutators::{ActiveMutators, Mutator};
+use super::rng::GameRng;
+use super::score::{MatchStats, Score};
+use crate::app_state::{AppState, GameOver, Victory};
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SAVE_DIR: &str = "save";
+const DAILY_FILE: &str = "save/daily.ron";
+const SECONDS_PER_DAY: u64 = 86_400;
+const DAILY_SEED_SALT: u64 = 0x9E37_79B9_7F4A_7C15;
+const DAILY_MUTATOR_COUNT: usize = 2;
+
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DailyChallenge::today())
+            .insert_resource(DailyHistory::load())
+            .add_systems(
+                OnEnter(AppState::Game),
+                begin_daily_attempt.before(LevelSet).run_if(daily),
+            )
+            .add_systems(Update, record_daily_result.run_if(daily));
+    }
+}
+
+#[derive(Resource)]
+pub struct DailyChallenge {
+    pub date: String,
+    pub seed: u64,
+    pub mutators: Vec<Mutator>,
+    pub scored: bool,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() / SECONDS_PER_DAY)
+            .unwrap_or_default();
+        let seed = days.wrapping_mul(DAILY_SEED_SALT);
+
+        let mut rng = GameRng::from_seed(seed);
+        let mut candidates = Mutator::ALL;
+        candidates.shuffle(&mut *rng);
+        let mut mutators: Vec<Mutator> = Vec::new();
+        for mutator in candidates {
+            if mutators.len() < DAILY_MUTATOR_COUNT && mutators.iter().all(|drawn| !mutator.excludes(*drawn)) {
+                mutators.push(mutator);
+            }
+        }
+
+        Self {
+            date: format_date(days),
+            seed,
+            mutators,
+            scored: false,
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        let today = Self::today();
+        if today.date != self.date {
+            *self = today;
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: String,
+    pub score: u32,
+    pub seconds: f32,
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct DailyHistory {
+    pub results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    fn load() -> Self {
+        fs::read_to_string(DAILY_FILE)
+            .ok()
+            .and_then(|contents| ron::de::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = fs::create_dir_all(Path::new(SAVE_DIR))
+            .map_err(|error| error.to_string())
+            .and_then(|_| ron::ser::to_string(self).map_err(|error| error.to_string()))
+            .and_then(|contents| fs::write(DAILY_FILE, contents).map_err(|error| error.to_string()));
+
+        if let Err(error) = result {
+            warn!("Failed to save daily results: {}", error);
+        }
+    }
+
+    pub fn result_for(&self, date: &str) -> Option<&DailyResult> {
+        self.results.iter().find(|result| result.date == date)
+    }
+}
+
+pub fn daily(game_mode: Res<GameMode>) -> bool {
+    *game_mode == GameMode::Daily
+}
+
+fn format_date(days: u64) -> String {
+    // Converts days since the Unix epoch to a proleptic Gregorian date.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn begin_daily_attempt(
+    mut challenge: ResMut<DailyChallenge>,
+    mut history: ResMut<DailyHistory>,
+    mut game_rng: ResMut<GameRng>,
+    mut mutators: ResMut<ActiveMutators>,
+) {
+    challenge.refresh();
+    *game_rng = GameRng::from_seed(challenge.seed);
+    mutators.0 = challenge.mutators.clone();
+
+    challenge.scored = history.result_for(&challenge.date).is_none();
+    if challenge.scored {
+        history.results.push(DailyResult {
+            date: challenge.date.clone(),
+            score: 0,
+            seconds: 0.0,
+        });
+        history.save();
+    }
+}
+
+fn record_daily_result(
+    mut game_over_event_reader: EventReader<GameOver>,
+    mut victory_event_reader: EventReader<Victory>,
+    mut challenge: ResMut<DailyChallenge>,
+    mut history: ResMut<DailyHistory>,
+    score: Res<Score>,
+    match_stats: Res<MatchStats>,
+) {
+    let finished = game_over_event_reader.read().count() + victory_event_reader.read().count() > 0;
+
+    if !finished || !challenge.scored {
+        return;
+    }
+    challenge.scored = false;
+
+    if let Some(result) = history.results.iter_mut().find(|result| result.date == challenge.date) {
+        result.score = score.total();
+        result.seconds = match_stats.seconds;
+        history.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_days_since_the_epoch() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(10_956), "1999-12-31");
+        assert_eq!(format_date(11_016), "2000-02-29");
+        assert_eq!(format_date(19_782), "2024-02-29");
+        assert_eq!(format_date(47_541), "2100-03-01");
+    }
+}
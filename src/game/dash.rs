@@ -0,0 +1,188 @@
+use super::movement::{BoundaryHitEvent, Locomotion, MovementSet, Velocity};
+use super::player::{Controls, Player, RemoteInput, Stunned};
+use super::state::running;
+use crate::app_state::AppState;
+use crate::settings::Settings;
+use bevy::prelude::*;
+
+const DASH_DISTANCE: f32 = 260.0;
+const DASH_TIME: f32 = 0.15;
+const DASH_COOLDOWN: f32 = 1.5;
+const TRAIL_INTERVAL: f32 = 0.02;
+const TRAIL_LIFETIME: f32 = 0.3;
+const TRAIL_ALPHA: f32 = 0.5;
+const TRAIL_Z: f32 = -0.1;
+
+pub struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(AppState::Game), despawn_dash_trail)
+            .add_systems(
+                Update,
+                (
+                    (tick_dash_cooldown, start_dash, dash_movement)
+                        .chain()
+                        .before(MovementSet),
+                    spawn_dash_trail,
+                    fade_dash_trail,
+                )
+                    .run_if(running),
+            )
+            .add_observer(stop_dash_at_boundary);
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Dash {
+    pub facing: Vec2,
+    cooldown: Timer,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(DASH_COOLDOWN, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self {
+            facing: Vec2::X,
+            cooldown,
+        }
+    }
+}
+
+impl Dash {
+    pub fn charge(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Dashing {
+    direction: Vec2,
+    timer: Timer,
+    trail_timer: Timer,
+}
+
+#[derive(Component)]
+struct DashTrail {
+    lifetime: Timer,
+}
+
+fn tick_dash_cooldown(mut dash_query: Query<&mut Dash>, time: Res<Time>) {
+    for mut dash in dash_query.iter_mut() {
+        dash.cooldown.tick(time.delta());
+    }
+}
+
+type DashReadyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Dash,
+        &'static Controls,
+        Option<&'static mut RemoteInput>,
+    ),
+    (With<Player>, Without<Dashing>, Without<Stunned>),
+>;
+
+fn start_dash(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut player_query: DashReadyQuery,
+) {
+    for (entity, mut dash, controls, remote) in player_query.iter_mut() {
+        let pressed = controls
+            .dash_key(&settings)
+            .is_some_and(|key| keyboard_input.just_pressed(key));
+        let requested = remote.is_some_and(|mut remote| std::mem::take(&mut remote.dash));
+        if !(pressed || requested) || !dash.cooldown.finished() {
+            continue;
+        }
+
+        dash.cooldown.reset();
+        commands.entity(entity).insert(Dashing {
+            direction: dash.facing,
+            timer: Timer::from_seconds(DASH_TIME, TimerMode::Once),
+            trail_timer: Timer::from_seconds(TRAIL_INTERVAL, TimerMode::Repeating),
+        });
+    }
+}
+
+fn dash_movement(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Velocity, &mut Dashing, Option<&Locomotion>)>,
+    time: Res<Time>,
+) {
+    for (entity, mut velocity, mut dashing, locomotion) in player_query.iter_mut() {
+        if dashing.timer.tick(time.delta()).finished() {
+            velocity.0 = dashing.direction * locomotion.map_or(0.0, |locomotion| locomotion.max_speed);
+            commands.entity(entity).remove::<Dashing>();
+        } else {
+            velocity.0 = dashing.direction * DASH_DISTANCE / DASH_TIME;
+        }
+    }
+}
+
+fn stop_dash_at_boundary(
+    trigger: Trigger<BoundaryHitEvent>,
+    mut commands: Commands,
+    dashing_query: Query<(), With<Dashing>>,
+) {
+    if dashing_query.contains(trigger.target()) {
+        commands.entity(trigger.target()).remove::<Dashing>();
+    }
+}
+
+fn spawn_dash_trail(
+    mut commands: Commands,
+    mut player_query: Query<(&Transform, &Mesh2d, &MeshMaterial2d<ColorMaterial>, &mut Dashing)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+) {
+    for (transform, mesh, material, mut dashing) in player_query.iter_mut() {
+        if !dashing.trail_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let color = materials
+            .get(&material.0)
+            .map_or(Color::WHITE, |material| material.color)
+            .with_alpha(TRAIL_ALPHA);
+        commands.spawn((
+            Mesh2d(mesh.0.clone()),
+            MeshMaterial2d(materials.add(color)),
+            transform.with_translation(transform.translation.with_z(TRAIL_Z)),
+            DashTrail {
+                lifetime: Timer::from_seconds(TRAIL_LIFETIME, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+fn fade_dash_trail(
+    mut commands: Commands,
+    mut trail_query: Query<(Entity, &mut DashTrail, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+) {
+    for (entity, mut trail, material) in trail_query.iter_mut() {
+        if trail.lifetime.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material
+                .color
+                .set_alpha(TRAIL_ALPHA * trail.lifetime.fraction_remaining());
+        }
+    }
+}
+
+fn despawn_dash_trail(mut commands: Commands, trail_query: Query<Entity, With<DashTrail>>) {
+    for entity in trail_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,22 +1,37 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use rand::random;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use super::player::{PLAYER_SIZE, Player};
+use super::arena::{Arena, Wrappable};
+use super::dash::Dashing;
+use super::level::{EnemyDef, Level};
+use super::mode::GameMode;
+use super::movement::{BoundaryHitEvent, Bounded, Collider, MovementSet, Velocity};
+use super::placement::{Placement, PlacementParams, SpawnSet};
+use super::player::{Player, Shielded, Stunned};
+use super::projectiles::{SHOOTER_FIRE_TIME, Shooter};
+use super::rng::GameRng;
+use super::rollback::retire;
 use super::state::running;
 use crate::app_state::{AppState, GameOver};
+use crate::sound::SpatialSounds;
 
-const NUMBER_OF_ENEMIES: usize = 4;
+pub const NUMBER_OF_ENEMIES: usize = 4;
 const ENEMY_SPEED: f32 = 200.0;
 const ENEMY_SIZE: f32 = 40.0;
-const ENEMY_SPAWN_TIME: f32 = 3.0;
+pub const ENEMY_SPAWN_TIME: f32 = 3.0;
+pub const ENEMY_SPAWN_SPACING: f32 = 160.0;
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EnemySpawnTimer>()
-            .add_systems(OnEnter(AppState::Game), spawn_enemies)
+            .init_resource::<EnemyDifficulty>()
+            .add_systems(
+                OnEnter(AppState::Game),
+                (reset_enemy_difficulty, spawn_enemies.in_set(SpawnSet::Enemies)),
+            )
             .add_systems(OnExit(AppState::Game), despawn_enemies)
             //.add_systems(Startup, spawn_enemies)
             .add_systems(
@@ -24,23 +39,102 @@ impl Plugin for EnemyPlugin {
                 (
                     tick_enemy_spawn_timer,
                     spawn_enemies_over_time,
-                    enemy_movement,
-                    update_enemy_direction,
-                    enemy_hit_player,
+                    drive_enemies.before(MovementSet),
+                    enemy_hit_player.after(MovementSet),
                 )
                     .run_if(running),
-            );
+            )
+            .add_observer(enemy_bounced)
+            .add_observer(kill_player);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EnemyArchetype {
+    #[default]
+    Basic,
+    Fast,
+    Heavy,
+    Shooter,
+}
+
+impl EnemyArchetype {
+    pub const ALL: [EnemyArchetype; 4] = [
+        EnemyArchetype::Basic,
+        EnemyArchetype::Fast,
+        EnemyArchetype::Heavy,
+        EnemyArchetype::Shooter,
+    ];
+
+    pub fn speed(self) -> f32 {
+        match self {
+            EnemyArchetype::Basic => ENEMY_SPEED,
+            EnemyArchetype::Fast => ENEMY_SPEED * 1.6,
+            EnemyArchetype::Heavy => ENEMY_SPEED * 0.6,
+            EnemyArchetype::Shooter => ENEMY_SPEED * 0.5,
+        }
+    }
+
+    pub fn size(self) -> f32 {
+        match self {
+            EnemyArchetype::Basic => ENEMY_SIZE,
+            EnemyArchetype::Fast => ENEMY_SIZE * 0.75,
+            EnemyArchetype::Heavy => ENEMY_SIZE * 1.6,
+            EnemyArchetype::Shooter => ENEMY_SIZE * 1.2,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            EnemyArchetype::Basic => Color::srgb_u8(255, 88, 88),
+            EnemyArchetype::Fast => Color::srgb_u8(255, 160, 64),
+            EnemyArchetype::Heavy => Color::srgb_u8(196, 72, 160),
+            EnemyArchetype::Shooter => Color::srgb_u8(72, 200, 220),
+        }
     }
 }
 
-#[derive(Component)]
-struct Enemy {
-    direction: Vec2,
+#[derive(Component, Clone)]
+#[require(Wrappable, Velocity, Bounded = Bounded::Bounce)]
+pub struct Enemy {
+    pub speed: f32,
 }
 
-#[derive(Resource)]
-struct EnemySpawnTimer {
-    timer: Timer,
+#[derive(Event)]
+pub struct EnemyBounceEvent {
+    pub position: Vec3,
+    pub normal: Vec2,
+}
+
+#[derive(Event)]
+pub struct PlayerHitEvent {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Event)]
+pub struct PlayerKilledEvent {
+    pub position: Vec3,
+}
+
+#[derive(Resource, Clone)]
+pub struct EnemyDifficulty {
+    pub speed_multiplier: f32,
+    pub spawn_rate_multiplier: f32,
+}
+
+impl Default for EnemyDifficulty {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            spawn_rate_multiplier: 1.0,
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct EnemySpawnTimer {
+    pub timer: Timer,
 }
 
 impl Default for EnemySpawnTimer {
@@ -53,34 +147,69 @@ impl Default for EnemySpawnTimer {
 
 fn spawn_enemies(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    placement: PlacementParams,
+    level: Res<Level>,
+    mut enemy_spawn_timer: ResMut<EnemySpawnTimer>,
+    mut game_rng: ResMut<GameRng>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    let window = window_query.single().unwrap();
+    enemy_spawn_timer.timer = Timer::from_seconds(level.enemy_spawn_time, TimerMode::Repeating);
 
-    for _ in 0..NUMBER_OF_ENEMIES {
-        spawn_enemy(&mut commands, &window, &mut meshes, &mut materials);
+    let mut placement = placement.placement();
+    for enemy in level.enemies.iter() {
+        spawn_enemy(
+            &mut commands,
+            &mut placement,
+            enemy,
+            &mut game_rng,
+            &mut meshes,
+            &mut materials,
+        );
     }
 }
 
-fn spawn_enemy(
+fn reset_enemy_difficulty(mut enemy_difficulty: ResMut<EnemyDifficulty>) {
+    *enemy_difficulty = EnemyDifficulty::default();
+}
+
+pub fn spawn_enemy(
     commands: &mut Commands,
-    window: &Window,
+    placement: &mut Placement,
+    enemy: &EnemyDef,
+    game_rng: &mut GameRng,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
-) {
-    let x = ENEMY_SIZE + random::<f32>() * (window.width() - (2.0 * ENEMY_SIZE));
-    let y = ENEMY_SIZE + random::<f32>() * (window.height() - (2.0 * ENEMY_SIZE));
+) -> Option<Entity> {
+    let size = enemy.archetype.size();
+    let radius = size / 2.0;
+    let position = match enemy.position {
+        Some(position) => {
+            placement.reserve(position, radius);
+            position
+        }
+        // With no free spot left the spawn is skipped rather than dropped onto a player.
+        None => placement.place(radius, ENEMY_SPAWN_SPACING, &[], game_rng)?,
+    };
+    let direction = enemy
+        .direction
+        .and_then(Vec2::try_normalize)
+        .unwrap_or_else(|| Vec2::new(game_rng.random::<f32>(), game_rng.random::<f32>()).normalize());
 
-    commands.spawn((
-        Mesh2d(meshes.add(Circle::new(ENEMY_SIZE / 2.0)).into()),
-        MeshMaterial2d(materials.add(Color::srgb_u8(255, 88, 88))),
-        Transform::from_xyz(x, y, 0.0),
+    let mut entity = commands.spawn((
+        Mesh2d(meshes.add(Circle::new(size / 2.0))),
+        MeshMaterial2d(materials.add(enemy.archetype.color())),
+        Transform::from_translation(position.extend(0.0)),
         Enemy {
-            direction: Vec2::new(random::<f32>(), random::<f32>()).normalize(),
+            speed: enemy.archetype.speed(),
         },
+        Velocity(direction * enemy.archetype.speed()),
+        Collider { radius },
     ));
+    if enemy.archetype == EnemyArchetype::Shooter {
+        entity.insert(Shooter::new(SHOOTER_FIRE_TIME));
+    }
+    Some(entity.id())
 }
 
 fn despawn_enemies(mut commands: Commands, enemy_query: Query<Entity, With<Enemy>>) {
@@ -89,104 +218,120 @@ fn despawn_enemies(mut commands: Commands, enemy_query: Query<Entity, With<Enemy
     }
 }
 
-fn enemy_movement(mut enemy_query: Query<(&mut Transform, &Enemy)>, time: Res<Time>) {
-    for (mut transform, enemy) in enemy_query.iter_mut() {
-        let direction: Vec3 = Vec3::new(enemy.direction.x, enemy.direction.y, 0.0);
-        transform.translation += direction * ENEMY_SPEED * time.delta_secs();
+fn drive_enemies(mut enemy_query: Query<(&mut Velocity, &Enemy)>, enemy_difficulty: Res<EnemyDifficulty>) {
+    for (mut velocity, enemy) in enemy_query.iter_mut() {
+        velocity.0 = velocity.normalize_or_zero() * enemy.speed * enemy_difficulty.speed_multiplier;
     }
 }
 
-fn update_enemy_direction(
+fn enemy_bounced(
+    trigger: Trigger<BoundaryHitEvent>,
     mut commands: Commands,
-    mut enemy_query: Query<(&mut Transform, &mut Enemy)>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
+    enemy_query: Query<(), With<Enemy>>,
+    sounds: SpatialSounds,
 ) {
-    let window = window_query.single().unwrap();
-
-    let half_enemy_size = ENEMY_SIZE / 2.0;
-
-    let x_min = half_enemy_size;
-    let x_max = window.width() - half_enemy_size;
-    let y_min = half_enemy_size;
-    let y_max = window.height() - half_enemy_size;
-
-    for (mut transform, mut enemy) in enemy_query.iter_mut() {
-        let mut translation = transform.translation;
-        let mut direction_changed: bool = false;
-
-        if translation.x < x_min {
-            translation.x = x_min;
-            enemy.direction.x *= -1.0;
-            direction_changed = true
-        }
+    if !enemy_query.contains(trigger.target()) {
+        return;
+    }
 
-        if translation.x > x_max {
-            translation.x = x_max;
-            enemy.direction.x *= -1.0;
-            direction_changed = true
-        }
+    let event = trigger.event();
+    sounds.play(&mut commands, "audio/pluck_001.ogg", event.position);
+    commands.trigger(EnemyBounceEvent {
+        position: event.position,
+        normal: event.normal,
+    });
+}
 
-        if translation.y < y_min {
-            translation.y = y_min;
-            enemy.direction.y *= -1.0;
-            direction_changed = true
-        }
+type PlayerHitQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Transform, &'static Collider),
+    (With<Player>, Without<Stunned>, Without<Shielded>, Without<Dashing>),
+>;
 
-        if translation.y > y_max {
-            translation.y = y_max;
-            enemy.direction.y *= -1.0;
-            direction_changed = true
-        }
+fn enemy_hit_player(
+    mut commands: Commands,
+    player_query: PlayerHitQuery,
+    enemy_query: Query<(&Transform, &Collider), With<Enemy>>,
+    arena: Res<Arena>,
+) {
+    for (player_entity, player_transform, player_collider) in player_query.iter() {
+        for (enemy_transform, enemy_collider) in enemy_query.iter() {
+            let min_distance = player_collider.radius + enemy_collider.radius;
 
-        if direction_changed {
-            transform.translation = translation;
-            commands.spawn(AudioPlayer::<AudioSource>(asset_server.load("audio/pluck_001.ogg")));
+            if hits(&arena, player_transform, enemy_transform, min_distance) {
+                commands.trigger(PlayerHitEvent {
+                    player: player_entity,
+                    position: player_transform.translation,
+                });
+                break;
+            }
         }
     }
 }
 
-fn enemy_hit_player(
+fn kill_player(
+    trigger: Trigger<PlayerHitEvent>,
     mut commands: Commands,
     mut game_over_event_writer: EventWriter<GameOver>,
-    mut player_query: Query<(Entity, &Transform), With<Player>>,
-    enemy_query: Query<&Transform, With<Enemy>>,
-    asset_server: Res<AssetServer>,
+    sounds: SpatialSounds,
+    game_mode: Res<GameMode>,
+    player_query: Query<Entity, With<Player>>,
 ) {
-    if let Ok((player_entity, player_transform)) = player_query.single_mut() {
-        let min_distance = PLAYER_SIZE / 2.0 + ENEMY_SIZE / 2.0;
+    let event = trigger.event();
+    if !player_query.contains(event.player) {
+        return;
+    }
 
-        for enemy_transform in enemy_query.iter() {
-            if hits(&player_transform, &enemy_transform, min_distance) {
-                commands.spawn(AudioPlayer::<AudioSource>(
-                    asset_server.load("audio/explosionCrunch_000.ogg"),
-                ));
+    // Time Attack stuns and Versus respawns the player, so the hit is not a death there.
+    if matches!(*game_mode, GameMode::TimeAttack | GameMode::Versus) {
+        sounds.play(&mut commands, "audio/pluck_002.ogg", event.position);
+        return;
+    }
 
-                commands.entity(player_entity).despawn();
-                game_over_event_writer.write(GameOver);
-            }
-        }
+    sounds.play(&mut commands, "audio/explosionCrunch_000.ogg", event.position);
+    commands.trigger(PlayerKilledEvent {
+        position: event.position,
+    });
+    retire(&mut commands, event.player);
+    if player_query.iter().all(|entity| entity == event.player) {
+        game_over_event_writer.write(GameOver);
     }
 }
 
-fn hits(transform1: &Transform, transform2: &Transform, min_distance: f32) -> bool {
-    let distance = transform1.translation.distance(transform2.translation);
+fn hits(arena: &Arena, transform1: &Transform, transform2: &Transform, min_distance: f32) -> bool {
+    let distance = arena.distance(transform1.translation, transform2.translation);
     distance < min_distance
 }
 
-fn tick_enemy_spawn_timer(mut enemy_spawn_timer: ResMut<EnemySpawnTimer>, time: Res<Time>) {
-    enemy_spawn_timer.timer.tick(time.delta());
+fn tick_enemy_spawn_timer(
+    mut enemy_spawn_timer: ResMut<EnemySpawnTimer>,
+    enemy_difficulty: Res<EnemyDifficulty>,
+    time: Res<Time>,
+) {
+    enemy_spawn_timer
+        .timer
+        .tick(time.delta().mul_f32(enemy_difficulty.spawn_rate_multiplier));
 }
 
 fn spawn_enemies_over_time(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    placement: PlacementParams,
+    mut game_rng: ResMut<GameRng>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     enemy_spawn_timer: ResMut<EnemySpawnTimer>,
+    level: Res<Level>,
 ) {
     if enemy_spawn_timer.timer.finished() {
-        let window = window_query.single().unwrap();
-        spawn_enemy(&mut commands, &window, &mut meshes, &mut materials);
+        let archetype = level.spawn_table.pick(&mut **game_rng);
+        spawn_enemy(
+            &mut commands,
+            &mut placement.placement(),
+            &EnemyDef { archetype, ..default() },
+            &mut game_rng,
+            &mut meshes,
+            &mut materials,
+        );
     }
 }
@@ -1,15 +1,41 @@
-use super::stars::CollectStarEvent;
+use super::boss::Boss;
+use super::coop::Party;
+use super::dash::Dash;
+use super::level::{Level, LevelSet};
+use super::mode::GameMode;
+use super::mutators::ActiveMutators;
+use super::player::{PLAYER_COLORS, Player};
+use super::score::Score;
+use super::state::running;
+use super::time_attack::{TimeAttackTimer, timed_match};
 use crate::app_state::AppState;
-use crate::ui::{box_node, hub_node, text_bundle};
+use crate::ui::{box_node, format_time, hub_node, text_bundle};
 use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+const DASH_SEGMENTS: usize = 12;
+const DASH_RING_SIZE: f32 = 64.0;
+const DASH_SEGMENT_SIZE: f32 = 10.0;
+const DASH_READY_COLOR: Color = Color::srgb(0.35, 0.75, 0.35);
+const DASH_CHARGED_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.8);
+const DASH_EMPTY_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
 
 pub struct HubPlugin;
 
 impl Plugin for HubPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Game), spawn_hub)
+        app.add_systems(OnEnter(AppState::Game), spawn_hub.after(LevelSet))
             .add_systems(OnExit(AppState::Game), despawn_hub)
-            .add_observer(collect_star);
+            .add_systems(
+                Update,
+                (
+                    update_score_display,
+                    update_time_attack_display.run_if(timed_match),
+                    update_boss_health_bar,
+                    update_dash_indicator,
+                )
+                    .run_if(running),
+            );
     }
 }
 
@@ -19,18 +45,152 @@ struct Hub;
 #[derive(Component)]
 struct ScoreDisplay;
 
-fn spawn_hub(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn((
-        Hub,
-        hub_node(),
-        children![(
-            box_node(),
+#[derive(Component)]
+struct TimeAttackDisplay;
+
+#[derive(Component)]
+struct PlayerScoreDisplay(usize);
+
+#[derive(Component)]
+struct DashSegment {
+    player: usize,
+    index: usize,
+}
+
+#[derive(Component)]
+struct BossHealthBar;
+
+#[derive(Component)]
+struct BossHealthFill;
+
+fn spawn_hub(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level: Res<Level>,
+    game_mode: Res<GameMode>,
+    party: Res<Party>,
+    mutators: Res<ActiveMutators>,
+) {
+    let objective = match level.win_condition {
+        Some(win_condition) => format!("{}: {}", level.name, win_condition.description()),
+        None => level.name.clone(),
+    };
+
+    let hub = commands
+        .spawn((
+            Hub,
+            hub_node(),
             children![
-                text_bundle(&asset_server, "Score: ", 48.0),
-                (ScoreDisplay, text_bundle(&asset_server, "0", 48.0))
-            ]
+                (
+                    box_node(),
+                    children![
+                        text_bundle(&asset_server, "Score: ", 48.0),
+                        (ScoreDisplay, text_bundle(&asset_server, "0", 48.0))
+                    ]
+                ),
+                (
+                    Node {
+                        width: Val::Auto,
+                        ..box_node()
+                    },
+                    children![text_bundle(&asset_server, &objective, 24.0)]
+                )
+            ],
+        ))
+        .id();
+
+    let player_count = party.player_count(*game_mode);
+    if player_count > 1 {
+        for (player, color) in PLAYER_COLORS.iter().enumerate().take(player_count) {
+            commands.entity(hub).with_children(|parent| {
+                parent.spawn(box_node()).with_children(|score_box| {
+                    score_box
+                        .spawn(text_bundle(&asset_server, &format!("P{}: ", player + 1), 32.0))
+                        .insert(TextColor(*color));
+                    score_box
+                        .spawn((PlayerScoreDisplay(player), text_bundle(&asset_server, "0", 32.0)))
+                        .insert(TextColor(*color));
+                });
+            });
+        }
+    }
+
+    commands.entity(hub).with_children(|parent| {
+        parent.spawn(box_node()).with_children(|dash_box| {
+            dash_box.spawn(text_bundle(&asset_server, "Dash ", 32.0));
+            for player in 0..player_count {
+                dash_box
+                    .spawn(Node {
+                        width: Val::Px(DASH_RING_SIZE),
+                        height: Val::Px(DASH_RING_SIZE),
+                        ..default()
+                    })
+                    .with_children(|ring| {
+                        for index in 0..DASH_SEGMENTS {
+                            let offset = Vec2::from_angle(TAU / 4.0 - index as f32 * TAU / DASH_SEGMENTS as f32)
+                                * (DASH_RING_SIZE - DASH_SEGMENT_SIZE)
+                                / 2.0;
+                            ring.spawn((
+                                DashSegment { player, index },
+                                Node {
+                                    position_type: PositionType::Absolute,
+                                    left: Val::Px((DASH_RING_SIZE - DASH_SEGMENT_SIZE) / 2.0 + offset.x),
+                                    top: Val::Px((DASH_RING_SIZE - DASH_SEGMENT_SIZE) / 2.0 - offset.y),
+                                    width: Val::Px(DASH_SEGMENT_SIZE),
+                                    height: Val::Px(DASH_SEGMENT_SIZE),
+                                    ..default()
+                                },
+                                BorderRadius::MAX,
+                                BackgroundColor(DASH_READY_COLOR),
+                            ));
+                        }
+                    });
+            }
+        });
+    });
+
+    commands.entity(hub).with_child((
+        BossHealthBar,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(24.0),
+            left: Val::Percent(50.0),
+            width: Val::Px(480.0),
+            height: Val::Px(20.0),
+            margin: UiRect::left(Val::Px(-240.0)),
+            padding: UiRect::all(Val::Px(3.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Visibility::Hidden,
+        children![(
+            BossHealthFill,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.85, 0.2, 0.45)),
         )],
     ));
+
+    if !mutators.0.is_empty() {
+        commands.entity(hub).with_child((
+            box_node(),
+            children![text_bundle(
+                &asset_server,
+                &format!("x{:.2}", mutators.score_multiplier()),
+                32.0
+            )],
+        ));
+    }
+
+    if matches!(*game_mode, GameMode::TimeAttack | GameMode::Versus) {
+        commands.entity(hub).with_child((
+            box_node(),
+            children![(TimeAttackDisplay, text_bundle(&asset_server, "", 48.0))],
+        ));
+    }
 }
 
 fn despawn_hub(mut commands: Commands, query: Query<Entity, With<Hub>>) {
@@ -39,9 +199,69 @@ fn despawn_hub(mut commands: Commands, query: Query<Entity, With<Hub>>) {
     }
 }
 
-fn collect_star(_trigger: Trigger<CollectStarEvent>, mut query: Query<&mut Text, With<ScoreDisplay>>) {
+fn update_score_display(
+    score: Res<Score>,
+    mut query: Query<&mut Text, With<ScoreDisplay>>,
+    mut player_query: Query<(&mut Text, &PlayerScoreDisplay), Without<ScoreDisplay>>,
+) {
+    if score.is_changed() {
+        for mut text in query.iter_mut() {
+            **text = score.total().to_string();
+        }
+        for (mut text, display) in player_query.iter_mut() {
+            **text = score.players[display.0].to_string();
+        }
+    }
+}
+
+fn update_time_attack_display(
+    time_attack_timer: Res<TimeAttackTimer>,
+    mut query: Query<&mut Text, With<TimeAttackDisplay>>,
+) {
     for mut text in query.iter_mut() {
-        let score = text.parse::<u32>().unwrap();
-        **text = (score + 1).to_string();
+        **text = format_time(time_attack_timer.timer.remaining_secs());
+    }
+}
+
+fn update_boss_health_bar(
+    boss_query: Query<&Boss>,
+    mut bar_query: Query<&mut Visibility, With<BossHealthBar>>,
+    mut fill_query: Query<&mut Node, With<BossHealthFill>>,
+) {
+    let boss = boss_query.iter().next();
+
+    for mut visibility in bar_query.iter_mut() {
+        *visibility = if boss.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    if let Some(boss) = boss {
+        for mut node in fill_query.iter_mut() {
+            node.width = Val::Percent(100.0 * boss.health as f32 / boss.max_health as f32);
+        }
+    }
+}
+
+fn update_dash_indicator(
+    player_query: Query<(&Player, &Dash)>,
+    mut segment_query: Query<(&DashSegment, &mut BackgroundColor)>,
+) {
+    for (segment, mut background_color) in segment_query.iter_mut() {
+        let charge = player_query
+            .iter()
+            .find(|(player, _)| player.index == segment.player)
+            .map_or(0.0, |(_, dash)| dash.charge());
+        let charged_segments = (charge * DASH_SEGMENTS as f32).floor() as usize;
+
+        background_color.0 = if charge >= 1.0 {
+            DASH_READY_COLOR
+        } else if segment.index < charged_segments {
+            DASH_CHARGED_COLOR
+        } else {
+            DASH_EMPTY_COLOR
+        };
     }
 }
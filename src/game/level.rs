@@ -0,0 +1,278 @@
+use super::arena::{Arena, ArenaBoundary};
+use super::enemies::{ENEMY_SPAWN_TIME, EnemyArchetype, NUMBER_OF_ENEMIES};
+use super::obstacles::{ObstacleDef, ObstacleLayout};
+use super::stars::{GOLDEN_STAR_CHANCE, MAX_STARS, STAR_COUNT, STAR_DRIFT_CHANCE, STAR_LIFETIME, STAR_SPAWN_TIME};
+use crate::app_state::AppState;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_LEVEL: &str = "levels/default.level.ron";
+const CAMPAIGN_LEVELS: [&str; 5] = [
+    "levels/campaign/01_first_steps.level.ron",
+    "levels/campaign/02_crossfire.level.ron",
+    "levels/campaign/03_pillars.level.ron",
+    "levels/campaign/04_heavyweights.level.ron",
+    "levels/campaign/05_gauntlet.level.ron",
+];
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .init_resource::<Level>()
+            .init_resource::<SelectedLevel>()
+            .add_systems(Startup, load_levels)
+            .add_systems(OnEnter(AppState::Game), apply_level.in_set(LevelSet))
+            .add_systems(OnEnter(AppState::Game), play_level_music.after(LevelSet))
+            .add_systems(OnExit(AppState::Game), stop_level_music);
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LevelSet;
+
+#[derive(Asset, Resource, TypePath, Clone, Debug, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub arena_size: Vec2,
+    #[serde(default)]
+    pub boundary: ArenaBoundary,
+    pub obstacles: Vec<ObstacleDef>,
+    pub enemies: Vec<EnemyDef>,
+    pub enemy_spawn_time: f32,
+    #[serde(default)]
+    pub spawn_table: SpawnTable,
+    pub star_count: usize,
+    pub star_spawn_time: f32,
+    pub star_regions: Vec<StarRegion>,
+    #[serde(default)]
+    pub star_rules: StarRules,
+    pub win_condition: Option<WinCondition>,
+    pub music: Option<String>,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        let arena = Arena::default();
+        Self {
+            name: "Endless".to_string(),
+            arena_size: arena.size(),
+            boundary: arena.boundary,
+            obstacles: ObstacleLayout::default().obstacles,
+            enemies: vec![EnemyDef::default(); NUMBER_OF_ENEMIES],
+            enemy_spawn_time: ENEMY_SPAWN_TIME,
+            spawn_table: SpawnTable::default(),
+            star_count: STAR_COUNT,
+            star_spawn_time: STAR_SPAWN_TIME,
+            star_regions: Vec::new(),
+            star_rules: StarRules::default(),
+            win_condition: None,
+            music: None,
+        }
+    }
+}
+
+impl Level {
+    pub fn star_lifetime(&self) -> Option<f32> {
+        // Stars that expire would let "collect every star" levels finish on their own.
+        match self.win_condition {
+            Some(WinCondition::AllStars) => None,
+            _ => self.star_rules.lifetime,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EnemyDef {
+    pub archetype: EnemyArchetype,
+    pub position: Option<Vec2>,
+    pub direction: Option<Vec2>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SpawnTable(pub Vec<SpawnWeight>);
+
+impl Default for SpawnTable {
+    fn default() -> Self {
+        Self(vec![SpawnWeight {
+            archetype: EnemyArchetype::Basic,
+            weight: 1.0,
+        }])
+    }
+}
+
+impl SpawnTable {
+    pub fn pick(&self, rng: &mut impl Rng) -> EnemyArchetype {
+        self.0
+            .choose_weighted(rng, |spawn| spawn.weight)
+            .map_or(EnemyArchetype::Basic, |spawn| spawn.archetype)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SpawnWeight {
+    pub archetype: EnemyArchetype,
+    pub weight: f32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct StarRegion {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StarRules {
+    pub lifetime: Option<f32>,
+    pub max_stars: usize,
+    pub drift_chance: f32,
+    pub golden_chance: f32,
+}
+
+impl Default for StarRules {
+    fn default() -> Self {
+        Self {
+            lifetime: Some(STAR_LIFETIME),
+            max_stars: MAX_STARS,
+            drift_chance: STAR_DRIFT_CHANCE,
+            golden_chance: GOLDEN_STAR_CHANCE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WinCondition {
+    Score(u32),
+    Survive(f32),
+    AllStars,
+}
+
+impl WinCondition {
+    pub fn description(&self) -> String {
+        match self {
+            WinCondition::Score(score) => format!("Collect {} stars", score),
+            WinCondition::Survive(seconds) => format!("Survive {} seconds", seconds),
+            WinCondition::AllStars => "Collect every star".to_string(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Levels {
+    pub default: Handle<Level>,
+    pub campaign: Vec<Handle<Level>>,
+}
+
+#[derive(Resource, Clone, Debug, Default, Eq, PartialEq)]
+pub enum SelectedLevel {
+    #[default]
+    Endless,
+    Campaign(usize),
+    Custom(Handle<Level>),
+}
+
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Level, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Component)]
+struct LevelMusic;
+
+fn load_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Levels {
+        default: asset_server.load(DEFAULT_LEVEL),
+        campaign: CAMPAIGN_LEVELS.iter().map(|path| asset_server.load(*path)).collect(),
+    });
+}
+
+fn apply_level(
+    levels: Res<Levels>,
+    selected_level: Res<SelectedLevel>,
+    level_assets: Res<Assets<Level>>,
+    mut level: ResMut<Level>,
+    mut arena: ResMut<Arena>,
+    mut obstacle_layout: ResMut<ObstacleLayout>,
+) {
+    let handle = match &*selected_level {
+        SelectedLevel::Endless => Some(&levels.default),
+        SelectedLevel::Campaign(index) => levels.campaign.get(*index),
+        SelectedLevel::Custom(handle) => Some(handle),
+    };
+
+    match handle.and_then(|handle| level_assets.get(handle)) {
+        Some(loaded_level) => *level = loaded_level.clone(),
+        None => warn!("{:?} is not loaded, keeping \"{}\"", *selected_level, level.name),
+    }
+
+    arena.width = level.arena_size.x;
+    arena.height = level.arena_size.y;
+    arena.boundary = level.boundary;
+    obstacle_layout.obstacles = level.obstacles.clone();
+}
+
+fn play_level_music(mut commands: Commands, level: Res<Level>, asset_server: Res<AssetServer>) {
+    if let Some(music) = &level.music {
+        commands.spawn((
+            AudioPlayer::<AudioSource>(asset_server.load(music.clone())),
+            PlaybackSettings::LOOP,
+            LevelMusic,
+        ));
+    }
+}
+
+fn stop_level_music(mut commands: Commands, music_query: Query<Entity, With<LevelMusic>>) {
+    for entity in music_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::GameRng;
+
+    #[test]
+    fn levels_without_a_spawn_table_only_spawn_basic_enemies() {
+        let level: Level = ron::de::from_str(include_str!("../../assets/levels/default.level.ron")).unwrap();
+        let mut game_rng = GameRng::from_seed(11);
+
+        assert!((0..100).all(|_| level.spawn_table.pick(&mut *game_rng) == EnemyArchetype::Basic));
+    }
+
+    #[test]
+    fn spawn_tables_are_read_from_level_files() {
+        let level: Level =
+            ron::de::from_str(include_str!("../../assets/levels/campaign/05_gauntlet.level.ron")).unwrap();
+        let mut game_rng = GameRng::from_seed(11);
+
+        assert!((0..100).any(|_| level.spawn_table.pick(&mut *game_rng) == EnemyArchetype::Shooter));
+    }
+}
@@ -0,0 +1,119 @@
+use super::daily::daily;
+use super::level::{LevelSet, SelectedLevel};
+use super::mutators::{ActiveMutators, SelectedMutators};
+use super::rng::{GameRng, MatchSeed};
+use super::score::Score;
+use crate::app_state::{AppState, GameOver, TimeUp, Victory};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const SAVE_DIR: &str = "save";
+const HIGH_SCORES_FILE: &str = "save/high_scores.ron";
+
+pub struct ModePlugin;
+
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .insert_resource(HighScores::load())
+            .add_systems(OnEnter(AppState::Game), prepare_run.before(LevelSet).run_if(not(daily)))
+            .add_systems(Update, record_high_score);
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    TimeAttack,
+    Survival,
+    Daily,
+    Versus,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Classic, GameMode::TimeAttack, GameMode::Survival];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::Survival => "Survival",
+            GameMode::Daily => "Daily Challenge",
+            GameMode::Versus => "Versus",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    scores: HashMap<GameMode, u32>,
+}
+
+impl HighScores {
+    fn load() -> Self {
+        fs::read_to_string(HIGH_SCORES_FILE)
+            .ok()
+            .and_then(|contents| ron::de::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let result = fs::create_dir_all(Path::new(SAVE_DIR))
+            .map_err(|error| error.to_string())
+            .and_then(|_| ron::ser::to_string(self).map_err(|error| error.to_string()))
+            .and_then(|contents| fs::write(HIGH_SCORES_FILE, contents).map_err(|error| error.to_string()));
+
+        if let Err(error) = result {
+            warn!("Failed to save high scores: {}", error);
+        }
+    }
+
+    pub fn best(&self, mode: GameMode) -> u32 {
+        self.scores.get(&mode).copied().unwrap_or(0)
+    }
+}
+
+fn prepare_run(
+    selected_level: Res<SelectedLevel>,
+    selected_mutators: Res<SelectedMutators>,
+    match_seed: Option<Res<MatchSeed>>,
+    mut game_rng: ResMut<GameRng>,
+    mut mutators: ResMut<ActiveMutators>,
+) {
+    *game_rng = match match_seed {
+        Some(match_seed) => GameRng::from_seed(match_seed.0),
+        None => GameRng::from_entropy(),
+    };
+    mutators.0 = match *selected_level {
+        SelectedLevel::Endless => selected_mutators.0.clone(),
+        _ => Vec::new(),
+    };
+}
+
+fn record_high_score(
+    mut game_over_event_reader: EventReader<GameOver>,
+    mut time_up_event_reader: EventReader<TimeUp>,
+    mut victory_event_reader: EventReader<Victory>,
+    selected_level: Res<SelectedLevel>,
+    game_mode: Res<GameMode>,
+    score: Res<Score>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let finished = game_over_event_reader.read().count()
+        + time_up_event_reader.read().count()
+        + victory_event_reader.read().count()
+        > 0;
+
+    if !finished || *game_mode == GameMode::Versus || !matches!(*selected_level, SelectedLevel::Endless) {
+        return;
+    }
+
+    if score.total() > high_scores.best(*game_mode) {
+        high_scores.scores.insert(*game_mode, score.total());
+        high_scores.save();
+    }
+}
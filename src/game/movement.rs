@@ -0,0 +1,141 @@
+use super::arena::Arena;
+use super::dash::Dashing;
+use super::obstacles::{Obstacle, push_out_of_obstacles};
+use super::state::running;
+use bevy::prelude::*;
+
+pub struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_locomotion, integrate_velocity, resolve_boundaries)
+                .chain()
+                .in_set(MovementSet)
+                .run_if(running),
+        );
+    }
+}
+
+#[derive(SystemSet, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct MovementSet;
+
+#[derive(Component, Clone, Copy, Default, Deref, DerefMut)]
+pub struct Velocity(pub Vec2);
+
+#[derive(Component, Clone, Copy)]
+pub struct Collider {
+    pub radius: f32,
+}
+
+#[derive(Component, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Bounded {
+    Clamp,
+    Bounce,
+}
+
+#[derive(Component, Clone, Copy, Default, Deref, DerefMut)]
+pub struct MoveIntent(pub Vec2);
+
+#[derive(Component, Clone, Copy)]
+#[require(Velocity, MoveIntent)]
+pub struct Locomotion {
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub friction: f32,
+}
+
+#[derive(Event)]
+pub struct BoundaryHitEvent {
+    pub position: Vec3,
+    pub normal: Vec2,
+}
+
+fn apply_locomotion(mut query: Query<(&mut Velocity, &MoveIntent, &Locomotion), Without<Dashing>>, time: Res<Time>) {
+    for (mut velocity, intent, locomotion) in query.iter_mut() {
+        let intent = intent.clamp_length_max(1.0);
+        let target = intent * locomotion.max_speed;
+
+        let rate = if intent == Vec2::ZERO {
+            locomotion.friction
+        } else if intent.dot(velocity.0) < 0.0 {
+            locomotion.acceleration + locomotion.friction
+        } else {
+            locomotion.acceleration
+        };
+
+        let change = (target - velocity.0).clamp_length_max(rate * time.delta_secs());
+        velocity.0 += change;
+    }
+}
+
+fn integrate_velocity(mut query: Query<(&mut Transform, &Velocity)>, time: Res<Time>) {
+    for (mut transform, velocity) in query.iter_mut() {
+        transform.translation += (velocity.0 * time.delta_secs()).extend(0.0);
+    }
+}
+
+fn resolve_boundaries(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut Velocity, &Collider, &Bounded)>,
+    obstacle_query: Query<(&Transform, &Obstacle), Without<Bounded>>,
+    arena: Res<Arena>,
+) {
+    for (entity, mut transform, mut velocity, collider, bounded) in query.iter_mut() {
+        let radius = collider.radius;
+        let mut translation = transform.translation;
+        let mut normal = Vec2::ZERO;
+
+        if !arena.wraps() {
+            if translation.x < radius {
+                translation.x = radius;
+                normal.x = 1.0;
+            } else if translation.x > arena.width - radius {
+                translation.x = arena.width - radius;
+                normal.x = -1.0;
+            }
+
+            if translation.y < radius {
+                translation.y = radius;
+                normal.y = 1.0;
+            } else if translation.y > arena.height - radius {
+                translation.y = arena.height - radius;
+                normal.y = -1.0;
+            }
+        }
+
+        for axis_normal in [Vec2::new(normal.x, 0.0), Vec2::new(0.0, normal.y)] {
+            respond(&mut velocity, *bounded, axis_normal);
+        }
+
+        if let Some(contact) = push_out_of_obstacles(obstacle_query.iter(), translation.truncate(), radius) {
+            translation = contact.position.extend(translation.z);
+            respond(&mut velocity, *bounded, contact.normal);
+            normal += contact.normal;
+        }
+
+        if translation != transform.translation {
+            transform.translation = translation;
+            commands.trigger_targets(
+                BoundaryHitEvent {
+                    position: translation,
+                    normal: normal.try_normalize().unwrap_or(velocity.normalize_or_zero()),
+                },
+                entity,
+            );
+        }
+    }
+}
+
+fn respond(velocity: &mut Velocity, bounded: Bounded, normal: Vec2) {
+    let approach = velocity.dot(normal);
+    if normal == Vec2::ZERO || approach >= 0.0 {
+        return;
+    }
+
+    match bounded {
+        Bounded::Clamp => velocity.0 -= approach * normal,
+        Bounded::Bounce => velocity.0 -= 2.0 * approach * normal,
+    }
+}
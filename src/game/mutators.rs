@@ -0,0 +1,113 @@
+mod enemy_speed;
+mod mirrored_controls;
+mod moving_stars;
+mod player_size;
+mod wrap_around;
+
+use bevy::prelude::*;
+use enemy_speed::EnemySpeedMutatorPlugin;
+pub use mirrored_controls::MirroredControls;
+use mirrored_controls::MirroredControlsMutatorPlugin;
+use moving_stars::MovingStarsMutatorPlugin;
+use player_size::PlayerSizeMutatorPlugin;
+use serde::{Deserialize, Serialize};
+use wrap_around::WrapAroundMutatorPlugin;
+
+pub struct MutatorPlugin;
+
+impl Plugin for MutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveMutators>()
+            .init_resource::<SelectedMutators>()
+            .add_plugins(EnemySpeedMutatorPlugin)
+            .add_plugins(PlayerSizeMutatorPlugin)
+            .add_plugins(MovingStarsMutatorPlugin)
+            .add_plugins(WrapAroundMutatorPlugin)
+            .add_plugins(MirroredControlsMutatorPlugin);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Mutator {
+    DoubleEnemySpeed,
+    TinyPlayer,
+    GiantPlayer,
+    MovingStars,
+    WrapAround,
+    MirroredControls,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 6] = [
+        Mutator::DoubleEnemySpeed,
+        Mutator::TinyPlayer,
+        Mutator::GiantPlayer,
+        Mutator::MovingStars,
+        Mutator::WrapAround,
+        Mutator::MirroredControls,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mutator::DoubleEnemySpeed => "Double Speed",
+            Mutator::TinyPlayer => "Tiny Player",
+            Mutator::GiantPlayer => "Giant Player",
+            Mutator::MovingStars => "Moving Stars",
+            Mutator::WrapAround => "No Walls",
+            Mutator::MirroredControls => "Mirrored",
+        }
+    }
+
+    pub fn excludes(self, other: Mutator) -> bool {
+        matches!(
+            (self, other),
+            (Mutator::TinyPlayer, Mutator::GiantPlayer) | (Mutator::GiantPlayer, Mutator::TinyPlayer)
+        )
+    }
+
+    pub fn score_multiplier(self) -> f32 {
+        match self {
+            Mutator::DoubleEnemySpeed => 1.5,
+            Mutator::TinyPlayer => 0.75,
+            Mutator::GiantPlayer => 1.5,
+            Mutator::MovingStars => 1.25,
+            Mutator::WrapAround => 0.9,
+            Mutator::MirroredControls => 1.5,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ActiveMutators(pub Vec<Mutator>);
+
+impl ActiveMutators {
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.0.contains(&mutator)
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        self.0.iter().map(|mutator| mutator.score_multiplier()).product()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SelectedMutators(pub Vec<Mutator>);
+
+impl SelectedMutators {
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.0.contains(&mutator)
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if let Some(index) = self.0.iter().position(|selected| *selected == mutator) {
+            self.0.remove(index);
+        } else {
+            self.0.retain(|selected| !mutator.excludes(*selected));
+            self.0.push(mutator);
+        }
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        self.0.iter().map(|mutator| mutator.score_multiplier()).product()
+    }
+}
@@ -0,0 +1,21 @@
+use super::{ActiveMutators, Mutator};
+use crate::game::enemies::Enemy;
+use bevy::prelude::*;
+
+const DOUBLE_SPEED_MULTIPLIER: f32 = 2.0;
+
+pub struct EnemySpeedMutatorPlugin;
+
+impl Plugin for EnemySpeedMutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(speed_up_enemy);
+    }
+}
+
+fn speed_up_enemy(trigger: Trigger<OnAdd, Enemy>, mut enemy_query: Query<&mut Enemy>, mutators: Res<ActiveMutators>) {
+    if mutators.contains(Mutator::DoubleEnemySpeed)
+        && let Ok(mut enemy) = enemy_query.get_mut(trigger.target())
+    {
+        enemy.speed *= DOUBLE_SPEED_MULTIPLIER;
+    }
+}
@@ -0,0 +1,20 @@
+use super::{ActiveMutators, Mutator};
+use crate::game::player::Player;
+use bevy::prelude::*;
+
+pub struct MirroredControlsMutatorPlugin;
+
+impl Plugin for MirroredControlsMutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(mirror_controls);
+    }
+}
+
+#[derive(Component)]
+pub struct MirroredControls;
+
+fn mirror_controls(trigger: Trigger<OnAdd, Player>, mut commands: Commands, mutators: Res<ActiveMutators>) {
+    if mutators.contains(Mutator::MirroredControls) {
+        commands.entity(trigger.target()).insert(MirroredControls);
+    }
+}
@@ -0,0 +1,31 @@
+use super::{ActiveMutators, Mutator};
+use crate::game::movement::Velocity;
+use crate::game::rng::GameRng;
+use crate::game::stars::Star;
+use bevy::prelude::*;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+const STAR_DRIFT_SPEED: f32 = 60.0;
+
+pub struct MovingStarsMutatorPlugin;
+
+impl Plugin for MovingStarsMutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(set_star_drifting);
+    }
+}
+
+fn set_star_drifting(
+    trigger: Trigger<OnAdd, Star>,
+    mut commands: Commands,
+    mutators: Res<ActiveMutators>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if mutators.contains(Mutator::MovingStars) {
+        let direction = Vec2::from_angle(game_rng.random::<f32>() * TAU);
+        commands
+            .entity(trigger.target())
+            .insert(Velocity(direction * STAR_DRIFT_SPEED));
+    }
+}
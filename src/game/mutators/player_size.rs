@@ -0,0 +1,33 @@
+use super::{ActiveMutators, Mutator};
+use crate::game::movement::Collider;
+use crate::game::player::{PLAYER_SIZE, Player};
+use bevy::prelude::*;
+
+const TINY_PLAYER_SCALE: f32 = 0.5;
+const GIANT_PLAYER_SCALE: f32 = 2.0;
+
+pub struct PlayerSizeMutatorPlugin;
+
+impl Plugin for PlayerSizeMutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(resize_player);
+    }
+}
+
+fn resize_player(
+    trigger: Trigger<OnAdd, Player>,
+    mut player_query: Query<(&mut Collider, &mut Transform), With<Player>>,
+    mutators: Res<ActiveMutators>,
+) {
+    let Ok((mut collider, mut transform)) = player_query.get_mut(trigger.target()) else {
+        return;
+    };
+
+    if mutators.contains(Mutator::TinyPlayer) {
+        collider.radius *= TINY_PLAYER_SCALE;
+    }
+    if mutators.contains(Mutator::GiantPlayer) {
+        collider.radius *= GIANT_PLAYER_SCALE;
+    }
+    transform.scale = Vec3::splat(collider.radius * 2.0 / PLAYER_SIZE);
+}
@@ -0,0 +1,19 @@
+use super::{ActiveMutators, Mutator};
+use crate::app_state::AppState;
+use crate::game::arena::{Arena, ArenaBoundary};
+use crate::game::level::LevelSet;
+use bevy::prelude::*;
+
+pub struct WrapAroundMutatorPlugin;
+
+impl Plugin for WrapAroundMutatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Game), remove_walls.after(LevelSet));
+    }
+}
+
+fn remove_walls(mutators: Res<ActiveMutators>, mut arena: ResMut<Arena>) {
+    if mutators.contains(Mutator::WrapAround) {
+        arena.boundary = ArenaBoundary::Wrap;
+    }
+}
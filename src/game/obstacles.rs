@@ -0,0 +1,175 @@
+use super::level::LevelSet;
+use crate::app_state::AppState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const OBSTACLE_COLOR: Color = Color::srgb(0.24, 0.36, 0.36);
+
+pub struct ObstaclePlugin;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ObstacleLayout>()
+            .add_systems(OnEnter(AppState::Game), spawn_obstacles.after(LevelSet))
+            .add_systems(OnExit(AppState::Game), despawn_obstacles);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum ObstacleShape {
+    Rectangle { width: f32, height: f32 },
+    Circle { radius: f32 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObstacleDef {
+    pub position: Vec2,
+    pub shape: ObstacleShape,
+}
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct ObstacleLayout {
+    pub obstacles: Vec<ObstacleDef>,
+}
+
+impl Default for ObstacleLayout {
+    fn default() -> Self {
+        Self {
+            obstacles: vec![
+                ObstacleDef {
+                    position: Vec2::new(320.0, 200.0),
+                    shape: ObstacleShape::Rectangle {
+                        width: 160.0,
+                        height: 40.0,
+                    },
+                },
+                ObstacleDef {
+                    position: Vec2::new(960.0, 520.0),
+                    shape: ObstacleShape::Rectangle {
+                        width: 160.0,
+                        height: 40.0,
+                    },
+                },
+                ObstacleDef {
+                    position: Vec2::new(320.0, 540.0),
+                    shape: ObstacleShape::Circle { radius: 50.0 },
+                },
+                ObstacleDef {
+                    position: Vec2::new(960.0, 180.0),
+                    shape: ObstacleShape::Circle { radius: 50.0 },
+                },
+            ],
+        }
+    }
+}
+
+impl ObstacleLayout {
+    pub fn overlaps(&self, position: Vec2, radius: f32) -> bool {
+        self.obstacles
+            .iter()
+            .any(|obstacle| obstacle.shape.contact(obstacle.position, position, radius).is_some())
+    }
+}
+
+#[derive(Component)]
+pub struct Obstacle {
+    shape: ObstacleShape,
+}
+
+pub struct Contact {
+    pub position: Vec2,
+    pub normal: Vec2,
+}
+
+impl ObstacleShape {
+    // Returns the outward normal and penetration depth of a circle overlapping this shape.
+    fn contact(&self, center: Vec2, position: Vec2, radius: f32) -> Option<(Vec2, f32)> {
+        match *self {
+            ObstacleShape::Circle {
+                radius: obstacle_radius,
+            } => {
+                let offset = position - center;
+                let distance = offset.length();
+                let min_distance = obstacle_radius + radius;
+                if distance >= min_distance {
+                    return None;
+                }
+                Some((offset.try_normalize().unwrap_or(Vec2::Y), min_distance - distance))
+            }
+            ObstacleShape::Rectangle { width, height } => {
+                let half_size = Vec2::new(width, height) / 2.0;
+                let local = position - center;
+                let closest = local.clamp(-half_size, half_size);
+                let offset = local - closest;
+
+                if offset != Vec2::ZERO {
+                    let distance = offset.length();
+                    if distance >= radius {
+                        return None;
+                    }
+                    return Some((offset / distance, radius - distance));
+                }
+
+                let penetration = half_size - local.abs();
+                if penetration.x < penetration.y {
+                    Some((Vec2::new(local.x.signum(), 0.0), penetration.x + radius))
+                } else {
+                    Some((Vec2::new(0.0, local.y.signum()), penetration.y + radius))
+                }
+            }
+        }
+    }
+}
+
+pub fn push_out_of_obstacles<'a>(
+    obstacles: impl IntoIterator<Item = (&'a Transform, &'a Obstacle)>,
+    position: Vec2,
+    radius: f32,
+) -> Option<Contact> {
+    let mut resolved = position;
+    let mut normal = Vec2::ZERO;
+
+    for (transform, obstacle) in obstacles {
+        let center = transform.translation.truncate();
+        if let Some((contact_normal, depth)) = obstacle.shape.contact(center, resolved, radius) {
+            resolved += contact_normal * depth;
+            normal += contact_normal;
+        }
+    }
+
+    if resolved == position {
+        return None;
+    }
+
+    Some(Contact {
+        position: resolved,
+        normal: normal.try_normalize().unwrap_or((resolved - position).normalize()),
+    })
+}
+
+fn spawn_obstacles(
+    mut commands: Commands,
+    layout: Res<ObstacleLayout>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for obstacle in layout.obstacles.iter() {
+        let mesh = match obstacle.shape {
+            ObstacleShape::Rectangle { width, height } => meshes.add(Rectangle::new(width, height)),
+            ObstacleShape::Circle { radius } => meshes.add(Circle::new(radius)),
+        };
+
+        commands.spawn((
+            Mesh2d(mesh),
+            MeshMaterial2d(materials.add(OBSTACLE_COLOR)),
+            Transform::from_translation(obstacle.position.extend(0.0)),
+            Obstacle { shape: obstacle.shape },
+        ));
+    }
+}
+
+fn despawn_obstacles(mut commands: Commands, obstacle_query: Query<Entity, With<Obstacle>>) {
+    for entity in obstacle_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,234 @@
+use super::boss::BossDefeatedEvent;
+use super::enemies::{EnemyBounceEvent, PlayerHitEvent, PlayerKilledEvent};
+use super::rollback::RollbackFrame;
+use super::stars::CollectStarEvent;
+use super::state::paused;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::random;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const PARTICLE_EFFECTS: &str = "effects/particles.effects.ron";
+const PARTICLE_Z: f32 = 0.5;
+const PARTICLE_DRAG: f32 = 3.0;
+const FADE_STEPS: usize = 16;
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ParticleEffects>()
+            .init_asset_loader::<ParticleEffectsLoader>()
+            .init_resource::<ParticleAssets>()
+            .add_systems(Update, build_particle_fades)
+            .add_systems(Update, update_particles.run_if(not(paused)))
+            .add_observer(star_collected)
+            .add_observer(enemy_bounced)
+            .add_observer(player_hit)
+            .add_observer(player_killed)
+            .add_observer(boss_defeated);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+enum ParticleKind {
+    StarPickup,
+    EnemyBounce,
+    PlayerHit,
+    PlayerDeath,
+    BossDefeat,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParticleEffect {
+    count: usize,
+    min_speed: f32,
+    max_speed: f32,
+    spread: f32,
+    lifetime: f32,
+    size: f32,
+    start_color: Color,
+    end_color: Color,
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+struct ParticleEffects(HashMap<ParticleKind, ParticleEffect>);
+
+#[derive(Default)]
+struct ParticleEffectsLoader;
+
+impl AssetLoader for ParticleEffectsLoader {
+    type Asset = ParticleEffects;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<ParticleEffects, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["effects.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct ParticleAssets {
+    mesh: Handle<Mesh>,
+    effects: Handle<ParticleEffects>,
+    // Particles step through a few shared materials as they fade instead of owning one each.
+    fades: HashMap<ParticleKind, Vec<Handle<ColorMaterial>>>,
+}
+
+impl FromWorld for ParticleAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Rectangle::new(1.0, 1.0));
+        Self {
+            mesh,
+            effects: world.resource::<AssetServer>().load(PARTICLE_EFFECTS),
+            fades: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Particle {
+    kind: ParticleKind,
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+fn build_particle_fades(
+    mut asset_events: EventReader<AssetEvent<ParticleEffects>>,
+    mut particle_assets: ResMut<ParticleAssets>,
+    effects: Res<Assets<ParticleEffects>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let changed = asset_events.read().any(|event| {
+        event.is_loaded_with_dependencies(&particle_assets.effects) || event.is_modified(&particle_assets.effects)
+    });
+    let Some(effects) = effects.get(&particle_assets.effects).filter(|_| changed) else {
+        return;
+    };
+
+    particle_assets.fades = effects
+        .0
+        .iter()
+        .map(|(kind, effect)| {
+            let fade = (0..FADE_STEPS)
+                .map(|step| {
+                    let t = step as f32 / (FADE_STEPS - 1) as f32;
+                    materials.add(effect.start_color.mix(&effect.end_color, t))
+                })
+                .collect();
+            (*kind, fade)
+        })
+        .collect();
+}
+
+#[derive(SystemParam)]
+struct Bursts<'w, 's> {
+    commands: Commands<'w, 's>,
+    particle_assets: Res<'w, ParticleAssets>,
+    effects: Res<'w, Assets<ParticleEffects>>,
+    rollback_frame: Option<Res<'w, RollbackFrame>>,
+}
+
+impl Bursts<'_, '_> {
+    fn spawn(&mut self, kind: ParticleKind, position: Vec3, direction: Vec2) {
+        if self.rollback_frame.as_ref().is_some_and(|frame| frame.replaying) {
+            return;
+        }
+
+        let effect = self
+            .effects
+            .get(&self.particle_assets.effects)
+            .and_then(|effects| effects.0.get(&kind));
+        let (Some(effect), Some(fade)) = (effect, self.particle_assets.fades.get(&kind)) else {
+            return;
+        };
+        let base_angle = direction.to_angle();
+
+        for _ in 0..effect.count {
+            let angle = base_angle + (random::<f32>() - 0.5) * effect.spread.to_radians();
+            let speed = effect.min_speed + random::<f32>() * (effect.max_speed - effect.min_speed);
+
+            self.commands.spawn((
+                Mesh2d(self.particle_assets.mesh.clone()),
+                MeshMaterial2d(fade[0].clone()),
+                Transform::from_xyz(position.x, position.y, PARTICLE_Z).with_scale(Vec3::splat(effect.size)),
+                Particle {
+                    kind,
+                    velocity: Vec2::from_angle(angle) * speed,
+                    lifetime: Timer::from_seconds(effect.lifetime, TimerMode::Once),
+                },
+            ));
+        }
+    }
+}
+
+fn star_collected(trigger: Trigger<CollectStarEvent>, mut bursts: Bursts) {
+    bursts.spawn(ParticleKind::StarPickup, trigger.event().position, Vec2::X);
+}
+
+fn enemy_bounced(trigger: Trigger<EnemyBounceEvent>, mut bursts: Bursts) {
+    let event = trigger.event();
+    bursts.spawn(ParticleKind::EnemyBounce, event.position, event.normal);
+}
+
+fn player_hit(trigger: Trigger<PlayerHitEvent>, mut bursts: Bursts) {
+    bursts.spawn(ParticleKind::PlayerHit, trigger.event().position, Vec2::X);
+}
+
+fn player_killed(trigger: Trigger<PlayerKilledEvent>, mut bursts: Bursts) {
+    bursts.spawn(ParticleKind::PlayerDeath, trigger.event().position, Vec2::X);
+}
+
+fn boss_defeated(trigger: Trigger<BossDefeatedEvent>, mut bursts: Bursts) {
+    bursts.spawn(ParticleKind::BossDefeat, trigger.event().position, Vec2::X);
+}
+
+fn update_particles(
+    mut commands: Commands,
+    mut particle_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut Particle,
+        &mut MeshMaterial2d<ColorMaterial>,
+    )>,
+    particle_assets: Res<ParticleAssets>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut particle, mut material) in particle_query.iter_mut() {
+        particle.lifetime.tick(time.delta());
+
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let drag = (1.0 - PARTICLE_DRAG * time.delta_secs()).max(0.0);
+        particle.velocity *= drag;
+        transform.translation += (particle.velocity * time.delta_secs()).extend(0.0);
+
+        let step = (particle.lifetime.fraction() * (FADE_STEPS - 1) as f32).round() as usize;
+        if let Some(fade) = particle_assets
+            .fades
+            .get(&particle.kind)
+            .and_then(|fade| fade.get(step))
+            && material.0 != *fade
+        {
+            material.0 = fade.clone();
+        }
+    }
+}
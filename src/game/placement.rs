@@ -0,0 +1,205 @@
+use super::arena::Arena;
+use super::level::LevelSet;
+use super::movement::Collider;
+use super::obstacles::ObstacleLayout;
+use super::projectiles::Projectile;
+use super::rng::GameRng;
+use crate::app_state::AppState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+
+const ARENA_MARGIN: f32 = 8.0;
+const PLACEMENT_ATTEMPTS: usize = 32;
+
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        // Each initial spawn has to see what the previous one placed, so commands are flushed in between.
+        app.configure_sets(
+            OnEnter(AppState::Game),
+            (SpawnSet::Players, SpawnSet::Enemies, SpawnSet::Stars)
+                .chain()
+                .after(LevelSet),
+        );
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpawnSet {
+    Players,
+    Enemies,
+    Stars,
+}
+
+#[derive(SystemParam)]
+pub struct PlacementParams<'w, 's> {
+    arena: Res<'w, Arena>,
+    obstacle_layout: Res<'w, ObstacleLayout>,
+    occupants: Query<'w, 's, (&'static Transform, &'static Collider, Option<&'static Projectile>)>,
+}
+
+impl PlacementParams<'_, '_> {
+    pub fn placement(&self) -> Placement<'_> {
+        Placement {
+            arena: &self.arena,
+            obstacle_layout: &self.obstacle_layout,
+            occupied: self
+                .occupants
+                .iter()
+                // Pooled projectiles keep their collider while parked, but they occupy nothing.
+                .filter(|(.., projectile)| projectile.is_none_or(|projectile| projectile.is_active()))
+                .map(|(transform, collider, _)| (transform.translation.truncate(), collider.radius))
+                .collect(),
+        }
+    }
+}
+
+pub struct Placement<'a> {
+    arena: &'a Arena,
+    obstacle_layout: &'a ObstacleLayout,
+    occupied: Vec<(Vec2, f32)>,
+}
+
+impl Placement<'_> {
+    pub fn place(&mut self, radius: f32, spacing: f32, regions: &[Rect], game_rng: &mut GameRng) -> Option<Vec2> {
+        let position = (0..PLACEMENT_ATTEMPTS)
+            .map(|_| self.random_point(radius, regions, game_rng))
+            .find(|position| self.is_free(*position, radius, spacing))?;
+
+        self.occupied.push((position, radius));
+        Some(position)
+    }
+
+    pub fn reserve(&mut self, position: Vec2, radius: f32) {
+        self.occupied.push((position, radius));
+    }
+
+    pub fn random_point(&self, radius: f32, regions: &[Rect], game_rng: &mut GameRng) -> Vec2 {
+        let inset = radius + ARENA_MARGIN;
+        let bounds = Rect::from_corners(Vec2::splat(inset), self.arena.size() - inset);
+        let area = if regions.is_empty() {
+            bounds
+        } else {
+            let region = bounds.intersect(regions[game_rng.random_range(0..regions.len())]);
+            if region.is_empty() { bounds } else { region }
+        };
+
+        area.min + Vec2::new(game_rng.random::<f32>(), game_rng.random::<f32>()) * area.size()
+    }
+
+    fn is_free(&self, position: Vec2, radius: f32, spacing: f32) -> bool {
+        !self.obstacle_layout.overlaps(position, radius + spacing)
+            && self.occupied.iter().all(|(other, other_radius)| {
+                self.arena.delta(position, *other).length() >= radius + other_radius + spacing
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::coop::Party;
+    use crate::game::enemies::{ENEMY_SPAWN_SPACING, Enemy, EnemyPlugin};
+    use crate::game::level::Level;
+    use crate::game::mode::GameMode;
+    use crate::game::player::{Player, PlayerPlugin};
+    use crate::game::stars::StarPlugin;
+
+    const RADIUS: f32 = 16.0;
+    const SPACING: f32 = 8.0;
+
+    fn placement<'a>(arena: &'a Arena, obstacle_layout: &'a ObstacleLayout) -> Placement<'a> {
+        Placement {
+            arena,
+            obstacle_layout,
+            occupied: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn placed_positions_keep_clear_of_the_edges_obstacles_and_each_other() {
+        let arena = Arena::default();
+        let obstacle_layout = ObstacleLayout::default();
+        let mut placement = placement(&arena, &obstacle_layout);
+        let mut game_rng = GameRng::from_seed(3);
+
+        let positions: Vec<Vec2> = (0..20)
+            .filter_map(|_| placement.place(RADIUS, SPACING, &[], &mut game_rng))
+            .collect();
+        assert_eq!(positions.len(), 20);
+
+        let inset = RADIUS + ARENA_MARGIN;
+        for (index, position) in positions.iter().enumerate() {
+            assert!(position.cmpge(Vec2::splat(inset)).all() && position.cmple(arena.size() - inset).all());
+            assert!(!obstacle_layout.overlaps(*position, RADIUS + SPACING));
+            for other in positions[index + 1..].iter() {
+                assert!(position.distance(*other) >= RADIUS * 2.0 + SPACING);
+            }
+        }
+    }
+
+    #[test]
+    fn placed_positions_stay_inside_the_requested_region() {
+        let arena = Arena::default();
+        let obstacle_layout = ObstacleLayout { obstacles: Vec::new() };
+        let mut placement = placement(&arena, &obstacle_layout);
+        let mut game_rng = GameRng::from_seed(5);
+        let region = Rect::new(500.0, 300.0, 700.0, 400.0);
+
+        for _ in 0..5 {
+            let position = placement.place(RADIUS, SPACING, &[region], &mut game_rng);
+            assert!(position.is_some_and(|position| region.contains(position)));
+        }
+    }
+
+    #[test]
+    fn placement_gives_up_when_nothing_is_free() {
+        let arena = Arena::default();
+        let obstacle_layout = ObstacleLayout { obstacles: Vec::new() };
+        let mut placement = placement(&arena, &obstacle_layout);
+        placement.reserve(arena.size() / 2.0, arena.size().length());
+
+        assert_eq!(placement.place(RADIUS, SPACING, &[], &mut GameRng::from_seed(1)), None);
+    }
+
+    #[test]
+    fn initial_spawns_keep_clear_of_each_other() {
+        for seed in 0..20 {
+            let mut app = App::new();
+            app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+                .init_asset::<Mesh>()
+                .init_asset::<ColorMaterial>()
+                .init_resource::<Arena>()
+                .init_resource::<ObstacleLayout>()
+                .init_resource::<Level>()
+                .init_resource::<Party>()
+                .init_resource::<GameMode>()
+                .insert_resource(GameRng::from_seed(seed))
+                .add_plugins((PlacementPlugin, PlayerPlugin, EnemyPlugin, StarPlugin));
+            app.world_mut().run_schedule(OnEnter(AppState::Game));
+
+            let world = app.world_mut();
+            let mut spawned_query = world.query::<(&Transform, &Collider, Has<Player>, Has<Enemy>)>();
+            let spawned: Vec<_> = spawned_query
+                .iter(world)
+                .map(|(transform, collider, player, enemy)| {
+                    (transform.translation.truncate(), collider.radius, player, enemy)
+                })
+                .collect();
+            assert!(spawned.len() > Level::default().enemies.len());
+
+            for (index, (position, radius, player, enemy)) in spawned.iter().enumerate() {
+                for (other, other_radius, other_player, other_enemy) in spawned[index + 1..].iter() {
+                    let spacing = if *player && *other_enemy || *enemy && *other_player {
+                        ENEMY_SPAWN_SPACING
+                    } else {
+                        0.0
+                    };
+                    assert!(position.distance(*other) >= radius + other_radius + spacing);
+                }
+            }
+        }
+    }
+}
@@ -1,37 +1,201 @@
+use super::arena::{Arena, Wrappable};
+use super::coop::Party;
+use super::dash::Dash;
+use super::mode::GameMode;
+use super::movement::{Bounded, Collider, Locomotion, MoveIntent, MovementSet};
+use super::mutators::MirroredControls;
+use super::placement::SpawnSet;
 use super::state::running;
 use crate::app_state::AppState;
+use crate::settings::Settings;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
 pub const PLAYER_SIZE: f32 = 64.0;
+pub const MAX_PLAYERS: usize = 2;
+pub const PLAYER_COLORS: [Color; MAX_PLAYERS] = [Color::srgb_u8(106, 210, 210), Color::srgb_u8(240, 150, 90)];
+const ARROWS_DASH_KEY: KeyCode = KeyCode::ShiftRight;
 const PLAYER_SPEED: f32 = 800.0;
+const PLAYER_ACCELERATION: f32 = 5000.0;
+const PLAYER_FRICTION: f32 = 3500.0;
+const GAMEPAD_DEADZONE: f32 = 0.2;
+const SHIELD_GAP: f32 = 8.0;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Game), spawn_player)
+        app.add_systems(OnEnter(AppState::Game), spawn_players.in_set(SpawnSet::Players))
             .add_systems(OnExit(AppState::Game), unspawn_player)
-            .add_systems(Update, (player_movement, confine_player_movement).run_if(running));
+            .add_systems(
+                Update,
+                (
+                    player_input.before(MovementSet),
+                    recover_from_stun,
+                    wear_off_shield,
+                    draw_shield,
+                )
+                    .run_if(running),
+            );
     }
 }
 
 #[derive(Component)]
-pub struct Player;
+#[require(Wrappable, Dash, Bounded = Bounded::Clamp)]
+pub struct Player {
+    pub index: usize,
+}
+
+#[derive(Component, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Controls {
+    Shared,
+    Wasd,
+    Arrows,
+    Remote,
+}
+
+impl Controls {
+    pub fn for_player(player_count: usize, index: usize) -> Self {
+        match (player_count, index) {
+            (1, _) => Controls::Shared,
+            (_, 0) => Controls::Wasd,
+            _ => Controls::Arrows,
+        }
+    }
+
+    pub fn direction(self, keys: &ButtonInput<KeyCode>, gamepads: &Query<&Gamepad>) -> Vec2 {
+        let [left, right, down, up] = self.keys();
+        let axis = |negative: &[KeyCode], positive: &[KeyCode]| {
+            keys.any_pressed(positive.iter().copied()) as i32 as f32
+                - keys.any_pressed(negative.iter().copied()) as i32 as f32
+        };
+        let mut direction = Vec2::new(axis(left, right), axis(down, up)).normalize_or_zero();
+
+        for (_, gamepad) in gamepads
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.uses_gamepad(*index))
+        {
+            let stick = gamepad.left_stick() + gamepad.dpad();
+            if stick.length() > GAMEPAD_DEADZONE && stick.length() > direction.length() {
+                direction = stick.clamp_length_max(1.0);
+            }
+        }
+
+        direction
+    }
+
+    fn keys(self) -> [&'static [KeyCode]; 4] {
+        match self {
+            Controls::Shared => [
+                &[KeyCode::ArrowLeft, KeyCode::KeyA],
+                &[KeyCode::ArrowRight, KeyCode::KeyD],
+                &[KeyCode::ArrowDown, KeyCode::KeyS],
+                &[KeyCode::ArrowUp, KeyCode::KeyW],
+            ],
+            Controls::Wasd => [&[KeyCode::KeyA], &[KeyCode::KeyD], &[KeyCode::KeyS], &[KeyCode::KeyW]],
+            Controls::Arrows => [
+                &[KeyCode::ArrowLeft],
+                &[KeyCode::ArrowRight],
+                &[KeyCode::ArrowDown],
+                &[KeyCode::ArrowUp],
+            ],
+            Controls::Remote => [&[], &[], &[], &[]],
+        }
+    }
+
+    fn uses_gamepad(self, gamepad: usize) -> bool {
+        match self {
+            Controls::Shared => true,
+            Controls::Wasd => gamepad == 0,
+            Controls::Arrows => gamepad == 1,
+            Controls::Remote => false,
+        }
+    }
 
-fn spawn_player(
+    pub fn dash_key(self, settings: &Settings) -> Option<KeyCode> {
+        match self {
+            Controls::Shared | Controls::Wasd => Some(settings.dash_key),
+            Controls::Arrows => Some(ARROWS_DASH_KEY),
+            Controls::Remote => None,
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct RemoteInput {
+    pub direction: Vec2,
+    pub dash: bool,
+}
+
+#[derive(Component, Clone)]
+pub struct Stunned {
+    pub timer: Timer,
+}
+
+#[derive(Component, Clone)]
+pub struct Shielded {
+    pub timer: Timer,
+}
+
+fn spawn_players(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    arena: Res<Arena>,
+    party: Res<Party>,
+    game_mode: Res<GameMode>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    let window = window_query.single().unwrap();
-    commands.spawn((
-        Mesh2d(meshes.add(Circle::new(PLAYER_SIZE / 2.0)).into()),
-        MeshMaterial2d(materials.add(Color::srgb_u8(106, 210, 210))),
-        Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
-        Player,
-    ));
+    let count = party.player_count(*game_mode);
+    for index in 0..count {
+        let offset = (index as f32 - (count - 1) as f32 / 2.0) * PLAYER_SIZE * 2.0;
+        spawn_player(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            index,
+            Controls::for_player(count, index),
+            arena.center().truncate() + Vec2::X * offset,
+        );
+    }
+}
+
+pub fn spawn_player(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    index: usize,
+    controls: Controls,
+    position: Vec2,
+) -> Entity {
+    commands
+        .spawn((
+            Mesh2d(meshes.add(Circle::new(PLAYER_SIZE / 2.0))),
+            MeshMaterial2d(materials.add(PLAYER_COLORS[index])),
+            Transform::from_translation(position.extend(0.0)),
+            Player { index },
+            controls,
+            Collider {
+                radius: PLAYER_SIZE / 2.0,
+            },
+            Locomotion {
+                max_speed: PLAYER_SPEED,
+                acceleration: PLAYER_ACCELERATION,
+                friction: PLAYER_FRICTION,
+            },
+        ))
+        .id()
+}
+
+pub fn nearest_player<'a, T>(
+    arena: &Arena,
+    position: Vec3,
+    players: impl Iterator<Item = (&'a Transform, T)>,
+) -> Option<(&'a Transform, T)> {
+    players.min_by(|(a, _), (b, _)| {
+        arena
+            .distance(position, a.translation)
+            .total_cmp(&arena.distance(position, b.translation))
+    })
 }
 
 fn unspawn_player(mut commands: Commands, player_query: Query<Entity, With<Player>>) {
@@ -40,70 +204,78 @@ fn unspawn_player(mut commands: Commands, player_query: Query<Entity, With<Playe
     }
 }
 
-fn player_movement(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<&mut Transform, With<Player>>,
-    time: Res<Time>,
-) {
-    if let Ok(mut transform) = player_query.single_mut() {
-        let mut direction = Vec3::ZERO;
-
-        match (
-            keys.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]),
-            keys.any_pressed([KeyCode::ArrowDown, KeyCode::KeyS]),
-            keys.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]),
-            keys.any_pressed([KeyCode::ArrowUp, KeyCode::KeyW]),
-        ) {
-            (true, false, false, false) => direction += Vec3::new(-1.0, 0.0, 0.0),
-            (false, true, false, false) => direction += Vec3::new(0.0, -1.0, 0.0),
-            (false, false, true, false) => direction += Vec3::new(1.0, 0.0, 0.0),
-            (false, false, false, true) => direction += Vec3::new(0.0, 1.0, 0.0),
-            (true, true, false, false) => direction += Vec3::new(-0.7142, -0.7142, 0.0),
-            (true, false, false, true) => direction += Vec3::new(-0.7142, 0.7142, 0.0),
-            (false, true, true, false) => direction += Vec3::new(0.7142, -0.7142, 0.0),
-            (false, false, true, true) => direction += Vec3::new(0.7142, 0.7142, 0.0),
-            (true, true, false, true) => direction += Vec3::new(-1.0, 0.0, 0.0),
-            (true, true, true, false) => direction += Vec3::new(0.0, -1.0, 0.0),
-            (false, true, true, true) => direction += Vec3::new(1.0, 0.0, 0.0),
-            (true, false, true, true) => direction += Vec3::new(0.0, 1.0, 0.0),
-            _ => direction = Vec3::ZERO,
+type PlayerInputQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut MoveIntent,
+        &'static mut Dash,
+        &'static Controls,
+        Option<&'static RemoteInput>,
+        Has<MirroredControls>,
+        Has<Stunned>,
+    ),
+    With<Player>,
+>;
+
+fn player_input(keys: Res<ButtonInput<KeyCode>>, gamepads: Query<&Gamepad>, mut player_query: PlayerInputQuery) {
+    for (mut intent, mut dash, controls, remote, mirrored, stunned) in player_query.iter_mut() {
+        let mut direction = match remote {
+            Some(remote) => remote.direction.clamp_length_max(1.0),
+            None => controls.direction(&keys, &gamepads),
+        };
+
+        if mirrored {
+            direction = -direction;
         }
 
-        if direction.length() > 0.0 {
-            direction = direction.normalize();
+        if stunned {
+            direction = Vec2::ZERO;
         }
 
-        transform.translation += direction * PLAYER_SPEED * time.delta_secs();
+        if direction != Vec2::ZERO {
+            dash.facing = direction.normalize();
+        }
+        intent.0 = direction;
     }
 }
 
-fn confine_player_movement(
-    mut player_query: Query<&mut Transform, With<Player>>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+fn recover_from_stun(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Stunned, &mut Visibility), With<Player>>,
+    time: Res<Time>,
 ) {
-    if let Ok(mut player_transform) = player_query.single_mut() {
-        let window = window_query.single().unwrap();
-
-        let half_player_size = PLAYER_SIZE / 2.0;
-
-        let x_min = half_player_size;
-        let x_max = window.width() - half_player_size;
-        let y_min = half_player_size;
-        let y_max = window.height() - half_player_size;
-
-        let mut translation = player_transform.translation;
-
-        if translation.x < x_min {
-            translation.x = x_min;
-        } else if translation.x > x_max {
-            translation.x = x_max;
+    for (entity, mut stunned, mut visibility) in player_query.iter_mut() {
+        if stunned.timer.tick(time.delta()).finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Stunned>();
+        } else if ((stunned.timer.elapsed_secs() * 10.0) as u32).is_multiple_of(2) {
+            *visibility = Visibility::Hidden;
+        } else {
+            *visibility = Visibility::Inherited;
         }
+    }
+}
 
-        if translation.y < y_min {
-            translation.y = y_min;
-        } else if translation.y > y_max {
-            translation.y = y_max;
+fn wear_off_shield(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Shielded), With<Player>>,
+    time: Res<Time>,
+) {
+    for (entity, mut shielded) in player_query.iter_mut() {
+        if shielded.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Shielded>();
         }
-        player_transform.translation = translation;
+    }
+}
+
+fn draw_shield(mut gizmos: Gizmos, player_query: Query<(&Transform, &Collider, &Shielded), With<Player>>) {
+    for (transform, collider, shielded) in player_query.iter() {
+        let alpha = shielded.timer.fraction_remaining().max(0.25);
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            collider.radius + SHIELD_GAP,
+            Color::srgba(0.55, 0.85, 1.0, alpha),
+        );
     }
 }
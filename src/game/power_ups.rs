@@ -0,0 +1,165 @@
+use super::arena::{Arena, Wrappable};
+use super::movement::{Bounded, Collider, MovementSet, Velocity};
+use super::placement::Placement;
+use super::player::{Player, Shielded};
+use super::rng::GameRng;
+use super::rollback::retire;
+use super::score::Score;
+use super::state::running;
+use crate::app_state::AppState;
+use crate::sound::SpatialSounds;
+use bevy::prelude::*;
+
+const POWER_UP_SIZE: f32 = 32.0;
+const POWER_UP_LIFETIME: f32 = 10.0;
+const SHIELD_POWER_UP_TIME: f32 = 5.0;
+const BONUS_POWER_UP_POINTS: u32 = 10;
+const POWER_UP_SCATTER: f32 = 160.0;
+const POWER_UP_SPACING: f32 = 16.0;
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PowerUpAssets>()
+            .add_systems(OnExit(AppState::Game), despawn_power_ups)
+            .add_systems(
+                Update,
+                (expire_power_ups, collect_power_ups.after(MovementSet)).run_if(running),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PowerUpKind {
+    Shield,
+    Bonus,
+}
+
+impl PowerUpKind {
+    fn color(self) -> Color {
+        match self {
+            PowerUpKind::Shield => Color::srgb(0.55, 0.85, 1.0),
+            PowerUpKind::Bonus => Color::srgb(1.0, 0.84, 0.2),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct PowerUpAssets {
+    mesh: Handle<Mesh>,
+    shield_material: Handle<ColorMaterial>,
+    bonus_material: Handle<ColorMaterial>,
+}
+
+impl PowerUpAssets {
+    fn material(&self, kind: PowerUpKind) -> Handle<ColorMaterial> {
+        match kind {
+            PowerUpKind::Shield => self.shield_material.clone(),
+            PowerUpKind::Bonus => self.bonus_material.clone(),
+        }
+    }
+}
+
+impl FromWorld for PowerUpAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Rhombus::new(POWER_UP_SIZE, POWER_UP_SIZE));
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        let shield_material = materials.add(PowerUpKind::Shield.color());
+        let bonus_material = materials.add(PowerUpKind::Bonus.color());
+        Self {
+            mesh,
+            shield_material,
+            bonus_material,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+#[require(
+    Wrappable,
+    Velocity,
+    Bounded = Bounded::Clamp,
+    Collider = Collider {
+        radius: POWER_UP_SIZE / 2.0
+    }
+)]
+pub struct PowerUp {
+    kind: PowerUpKind,
+    lifetime: Timer,
+}
+
+pub fn spawn_power_up(
+    commands: &mut Commands,
+    power_up_assets: &PowerUpAssets,
+    placement: &mut Placement,
+    kind: PowerUpKind,
+    near: Vec2,
+    game_rng: &mut GameRng,
+) {
+    let radius = POWER_UP_SIZE / 2.0;
+    let region = Rect::from_center_half_size(near, Vec2::splat(POWER_UP_SCATTER));
+    let position = placement
+        .place(radius, POWER_UP_SPACING, &[region], game_rng)
+        .unwrap_or(near);
+
+    commands.spawn((
+        Mesh2d(power_up_assets.mesh.clone()),
+        MeshMaterial2d(power_up_assets.material(kind)),
+        Transform::from_translation(position.extend(0.0)),
+        PowerUp {
+            kind,
+            lifetime: Timer::from_seconds(POWER_UP_LIFETIME, TimerMode::Once),
+        },
+    ));
+}
+
+fn despawn_power_ups(mut commands: Commands, query: Query<Entity, With<PowerUp>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn expire_power_ups(mut commands: Commands, mut query: Query<(Entity, &mut PowerUp)>, time: Res<Time>) {
+    for (entity, mut power_up) in query.iter_mut() {
+        if power_up.lifetime.tick(time.delta()).finished() {
+            retire(&mut commands, entity);
+        }
+    }
+}
+
+fn collect_power_ups(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Transform, &Collider, &Player)>,
+    power_up_query: Query<(Entity, &Transform, &Collider, &PowerUp)>,
+    sounds: SpatialSounds,
+    arena: Res<Arena>,
+    mut score: ResMut<Score>,
+) {
+    for (entity, transform, collider, power_up) in power_up_query.iter() {
+        let collector = player_query.iter().find(|(_, player_transform, player_collider, _)| {
+            arena.distance(player_transform.translation, transform.translation)
+                <= player_collider.radius + collider.radius
+        });
+        let Some((player_entity, _, _, player)) = collector else {
+            continue;
+        };
+
+        match power_up.kind {
+            PowerUpKind::Shield => {
+                commands.entity(player_entity).insert(Shielded {
+                    timer: Timer::from_seconds(SHIELD_POWER_UP_TIME, TimerMode::Once),
+                });
+            }
+            PowerUpKind::Bonus => {
+                score.value += BONUS_POWER_UP_POINTS;
+                score.players[player.index] += BONUS_POWER_UP_POINTS;
+            }
+        }
+
+        sounds.play(&mut commands, "audio/laserLarge_000.ogg", transform.translation);
+        retire(&mut commands, entity);
+    }
+}
@@ -0,0 +1,405 @@
+use super::arena::{Arena, Wrappable};
+use super::boss::{Boss, BossHitEvent};
+use super::dash::Dashing;
+use super::enemies::PlayerHitEvent;
+use super::movement::{BoundaryHitEvent, Bounded, Collider, MovementSet, Velocity};
+use super::player::{Player, Shielded, Stunned, nearest_player};
+use super::state::running;
+use crate::app_state::AppState;
+use crate::sound::SpatialSounds;
+use bevy::prelude::*;
+
+const PROJECTILE_SIZE: f32 = 12.0;
+const PROJECTILE_SPEED: f32 = 420.0;
+const PROJECTILE_LIFETIME: f32 = 6.0;
+const PROJECTILE_BOUNCES: u32 = 1;
+const PROJECTILE_POOL_SIZE: usize = 48;
+const REFLECTED_SPEED_MULTIPLIER: f32 = 1.5;
+pub const SHOOTER_FIRE_TIME: f32 = 2.2;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProjectileAssets>()
+            .init_resource::<ProjectilePool>()
+            .add_systems(OnEnter(AppState::Game), fill_projectile_pool)
+            .add_systems(OnExit(AppState::Game), empty_projectile_pool)
+            .add_systems(
+                Update,
+                (
+                    fire_projectiles,
+                    expire_projectiles,
+                    projectile_hit_player,
+                    projectile_hit_boss,
+                )
+                    .chain()
+                    .after(MovementSet)
+                    .run_if(running),
+            )
+            .add_observer(launch_projectile)
+            .add_observer(projectile_bounced);
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Shooter {
+    fire_timer: Timer,
+    leads: bool,
+}
+
+impl Shooter {
+    pub fn new(fire_time: f32) -> Self {
+        Self {
+            fire_timer: Timer::from_seconds(fire_time, TimerMode::Repeating),
+            leads: false,
+        }
+    }
+}
+
+#[derive(Component, Clone, Default)]
+#[require(
+    Wrappable,
+    Velocity,
+    Bounded = Bounded::Bounce,
+    Collider = Collider {
+        radius: PROJECTILE_SIZE / 2.0
+    }
+)]
+pub struct Projectile {
+    bounces: u32,
+    lifetime: Timer,
+    active: bool,
+    reflected: bool,
+}
+
+impl Projectile {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+#[derive(Event)]
+struct FireProjectileEvent {
+    position: Vec2,
+    velocity: Vec2,
+}
+
+#[derive(Resource)]
+struct ProjectileAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+    reflected_material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for ProjectileAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Circle::new(PROJECTILE_SIZE / 2.0));
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        let material = materials.add(Color::srgb_u8(255, 224, 96));
+        let reflected_material = materials.add(Color::srgb_u8(120, 220, 255));
+        Self {
+            mesh,
+            material,
+            reflected_material,
+        }
+    }
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct ProjectilePool {
+    free: Vec<Entity>,
+}
+
+impl ProjectilePool {
+    fn release(
+        &mut self,
+        entity: Entity,
+        projectile: &mut Projectile,
+        velocity: &mut Velocity,
+        visibility: &mut Visibility,
+    ) {
+        projectile.active = false;
+        velocity.0 = Vec2::ZERO;
+        *visibility = Visibility::Hidden;
+        self.free.push(entity);
+    }
+}
+
+fn fill_projectile_pool(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_assets: Res<ProjectileAssets>,
+) {
+    for _ in 0..PROJECTILE_POOL_SIZE {
+        let entity = commands
+            .spawn((
+                Mesh2d(projectile_assets.mesh.clone()),
+                MeshMaterial2d(projectile_assets.material.clone()),
+                Transform::default(),
+                Visibility::Hidden,
+                Projectile::default(),
+            ))
+            .id();
+        pool.free.push(entity);
+    }
+}
+
+fn empty_projectile_pool(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_query: Query<Entity, With<Projectile>>,
+) {
+    for entity in projectile_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    pool.free.clear();
+}
+
+fn fire_projectiles(
+    mut commands: Commands,
+    mut shooter_query: Query<(&Transform, &mut Shooter)>,
+    player_query: Query<(&Transform, &Velocity), With<Player>>,
+    arena: Res<Arena>,
+    time: Res<Time>,
+) {
+    for (transform, mut shooter) in shooter_query.iter_mut() {
+        if !shooter.fire_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let Some((player_transform, player_velocity)) =
+            nearest_player(&arena, transform.translation, player_query.iter())
+        else {
+            continue;
+        };
+
+        let position = transform.translation.truncate();
+        let offset = arena.delta(position, player_transform.translation.truncate());
+        let direction = if shooter.leads {
+            lead_direction(offset, player_velocity.0, PROJECTILE_SPEED)
+        } else {
+            offset.normalize_or_zero()
+        };
+        shooter.leads = !shooter.leads;
+
+        if direction != Vec2::ZERO {
+            commands.trigger(FireProjectileEvent {
+                position,
+                velocity: direction * PROJECTILE_SPEED,
+            });
+        }
+    }
+}
+
+fn lead_direction(offset: Vec2, target_velocity: Vec2, speed: f32) -> Vec2 {
+    let a = target_velocity.length_squared() - speed * speed;
+    let b = 2.0 * offset.dot(target_velocity);
+    let c = offset.length_squared();
+    let discriminant = b * b - 4.0 * a * c;
+
+    let time = if a.abs() < f32::EPSILON {
+        -c / b
+    } else if discriminant >= 0.0 {
+        let root = discriminant.sqrt();
+        [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+            .into_iter()
+            .filter(|time| *time > 0.0)
+            .fold(f32::INFINITY, f32::min)
+    } else {
+        f32::INFINITY
+    };
+
+    if time.is_finite() && time > 0.0 {
+        (offset + target_velocity * time).normalize_or_zero()
+    } else {
+        offset.normalize_or_zero()
+    }
+}
+
+fn launch_projectile(
+    trigger: Trigger<FireProjectileEvent>,
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_assets: Res<ProjectileAssets>,
+    sounds: SpatialSounds,
+) {
+    let event = trigger.event();
+    let entity = pool
+        .free
+        .pop()
+        .unwrap_or_else(|| commands.spawn(Mesh2d(projectile_assets.mesh.clone())).id());
+
+    commands.entity(entity).insert((
+        MeshMaterial2d(projectile_assets.material.clone()),
+        Transform::from_translation(event.position.extend(0.0)),
+        Visibility::Visible,
+        Velocity(event.velocity),
+        Projectile {
+            bounces: PROJECTILE_BOUNCES,
+            lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+            active: true,
+            reflected: false,
+        },
+    ));
+    sounds.play(&mut commands, "audio/pluck_002.ogg", event.position.extend(0.0));
+}
+
+fn expire_projectiles(
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Velocity, &mut Visibility)>,
+    mut pool: ResMut<ProjectilePool>,
+    time: Res<Time>,
+) {
+    for (entity, mut projectile, mut velocity, mut visibility) in projectile_query.iter_mut() {
+        if projectile.active && projectile.lifetime.tick(time.delta()).finished() {
+            pool.release(entity, &mut projectile, &mut velocity, &mut visibility);
+        }
+    }
+}
+
+fn projectile_bounced(
+    trigger: Trigger<BoundaryHitEvent>,
+    mut projectile_query: Query<(&mut Projectile, &mut Velocity, &mut Visibility)>,
+    mut pool: ResMut<ProjectilePool>,
+) {
+    let entity = trigger.target();
+    let Ok((mut projectile, mut velocity, mut visibility)) = projectile_query.get_mut(entity) else {
+        return;
+    };
+
+    if !projectile.active {
+        return;
+    }
+
+    if projectile.bounces == 0 {
+        pool.release(entity, &mut projectile, &mut velocity, &mut visibility);
+    } else {
+        projectile.bounces -= 1;
+    }
+}
+
+type ProjectileQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Collider,
+        &'static mut Projectile,
+        &'static mut Velocity,
+        &'static mut Visibility,
+    ),
+>;
+
+type ProjectileTargetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Transform, &'static Collider, Has<Shielded>),
+    (With<Player>, Without<Stunned>, Without<Dashing>),
+>;
+
+fn projectile_hit_player(
+    mut commands: Commands,
+    player_query: ProjectileTargetQuery,
+    mut projectile_query: ProjectileQuery,
+    mut pool: ResMut<ProjectilePool>,
+    projectile_assets: Res<ProjectileAssets>,
+    arena: Res<Arena>,
+) {
+    let mut hit_players = Vec::new();
+
+    for (entity, transform, collider, mut projectile, mut velocity, mut visibility) in projectile_query.iter_mut() {
+        if !projectile.active || projectile.reflected {
+            continue;
+        }
+
+        let target = player_query
+            .iter()
+            .find(|(player_entity, player_transform, player_collider, _)| {
+                let min_distance = player_collider.radius + collider.radius;
+                !hit_players.contains(player_entity)
+                    && arena.distance(player_transform.translation, transform.translation) < min_distance
+            });
+        let Some((player_entity, player_transform, _, shielded)) = target else {
+            continue;
+        };
+
+        if shielded {
+            let away = arena
+                .delta(
+                    player_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                )
+                .try_normalize()
+                .unwrap_or(-velocity.normalize_or_zero());
+            velocity.0 = away * velocity.length() * REFLECTED_SPEED_MULTIPLIER;
+            projectile.reflected = true;
+            projectile.lifetime.reset();
+            commands
+                .entity(entity)
+                .insert(MeshMaterial2d(projectile_assets.reflected_material.clone()));
+            continue;
+        }
+
+        pool.release(entity, &mut projectile, &mut velocity, &mut visibility);
+        hit_players.push(player_entity);
+        commands.trigger(PlayerHitEvent {
+            player: player_entity,
+            position: player_transform.translation,
+        });
+    }
+}
+
+fn projectile_hit_boss(
+    mut commands: Commands,
+    boss_query: Query<(Entity, &Transform, &Collider), With<Boss>>,
+    mut projectile_query: ProjectileQuery,
+    mut pool: ResMut<ProjectilePool>,
+    arena: Res<Arena>,
+) {
+    for (entity, transform, collider, mut projectile, mut velocity, mut visibility) in projectile_query.iter_mut() {
+        if !projectile.active || !projectile.reflected {
+            continue;
+        }
+
+        for (boss_entity, boss_transform, boss_collider) in boss_query.iter() {
+            let min_distance = boss_collider.radius + collider.radius;
+            if arena.distance(boss_transform.translation, transform.translation) < min_distance {
+                pool.release(entity, &mut projectile, &mut velocity, &mut visibility);
+                commands.trigger(BossHitEvent {
+                    boss: boss_entity,
+                    damage: 1,
+                });
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lead_direction_aims_at_a_stationary_target() {
+        let direction = lead_direction(Vec2::new(30.0, 40.0), Vec2::ZERO, 200.0);
+        assert!((direction - Vec2::new(0.6, 0.8)).length() < 1e-5);
+    }
+
+    #[test]
+    fn lead_direction_intercepts_a_moving_target() {
+        let direction = lead_direction(Vec2::new(100.0, 0.0), Vec2::new(0.0, 100.0), 200.0);
+        assert!((direction - Vec2::new(3.0_f32.sqrt() / 2.0, 0.5)).length() < 1e-5);
+    }
+
+    #[test]
+    fn lead_direction_falls_back_to_the_target_when_it_cannot_be_caught() {
+        assert_eq!(
+            lead_direction(Vec2::new(100.0, 0.0), Vec2::new(300.0, 0.0), 200.0),
+            Vec2::X
+        );
+        assert_eq!(lead_direction(Vec2::ZERO, Vec2::new(0.0, 50.0), 200.0), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::from_entropy());
+    }
+}
+
+#[derive(Resource, Clone, Copy)]
+pub struct MatchSeed(pub u64);
+
+#[derive(Resource, Clone, Deref, DerefMut)]
+pub struct GameRng(StdRng);
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+
+    pub fn from_entropy() -> Self {
+        Self(StdRng::from_os_rng())
+    }
+}
@@ -0,0 +1,399 @@
+use super::boss::{Boss, BossSpawner, Minion};
+use super::coop::DownedPlayer;
+use super::dash::{Dash, Dashing};
+use super::enemies::{Enemy, EnemyDifficulty, EnemySpawnTimer};
+use super::movement::{Collider, MoveIntent, Velocity};
+use super::player::{Shielded, Stunned};
+use super::power_ups::PowerUp;
+use super::projectiles::{Projectile, ProjectilePool, Shooter};
+use super::rng::GameRng;
+use super::score::{MatchStats, Score};
+use super::stars::{GoldenStar, Star, StarLifetime, StarSpawnTimer};
+use super::time_attack::TimeAttackTimer;
+use crate::app_state::AppState;
+use bevy::ecs::entity_disabling::Disabled;
+use bevy::ecs::error::warn;
+use bevy::prelude::*;
+use rand::Rng;
+use std::any::Any;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+const ROLLED_BACK: [RolledBack; 30] = [
+    RolledBack::component::<Transform>(),
+    RolledBack::component::<Visibility>(),
+    RolledBack::component::<MeshMaterial2d<ColorMaterial>>(),
+    RolledBack::component::<Velocity>(),
+    RolledBack::component::<MoveIntent>(),
+    RolledBack::component::<Collider>(),
+    RolledBack::component::<Enemy>(),
+    RolledBack::component::<Dash>(),
+    RolledBack::component::<Dashing>(),
+    RolledBack::component::<Stunned>(),
+    RolledBack::component::<Shielded>(),
+    RolledBack::component::<Star>(),
+    RolledBack::component::<GoldenStar>(),
+    RolledBack::component::<StarLifetime>(),
+    RolledBack::component::<Shooter>(),
+    RolledBack::component::<Projectile>(),
+    RolledBack::component::<Boss>(),
+    RolledBack::component::<Minion>(),
+    RolledBack::component::<PowerUp>(),
+    RolledBack::component::<DownedPlayer>(),
+    RolledBack::resource::<Score>(),
+    RolledBack::resource::<MatchStats>(),
+    RolledBack::resource::<EnemySpawnTimer>(),
+    RolledBack::resource::<EnemyDifficulty>(),
+    RolledBack::resource::<StarSpawnTimer>(),
+    RolledBack::resource::<BossSpawner>(),
+    RolledBack::resource::<ProjectilePool>(),
+    RolledBack::resource::<TimeAttackTimer>(),
+    RolledBack::resource::<GameRng>(),
+    RolledBack::resource::<RollbackFrame>(),
+];
+
+pub struct RollbackPlugin;
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(AppState::Game), despawn_retired)
+            .add_observer(track_rollback_entity);
+    }
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct RollbackFrame {
+    pub tick: u32,
+    pub simulating: bool,
+    // Set while ticks are resimulated after a misprediction, which already played their effects once.
+    pub replaying: bool,
+    next_id: u32,
+}
+
+// Wall-clock systems must not advance once per resimulated tick.
+pub fn simulating(frame: Option<Res<RollbackFrame>>) -> bool {
+    frame.is_some_and(|frame| frame.simulating)
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct Rollback(u32);
+
+#[derive(Component, Clone, Copy)]
+struct Retired(u32);
+
+struct RolledBack {
+    save: fn(&World, &[Entity]) -> Box<dyn Any + Send + Sync>,
+    restore: fn(&mut World, &[Entity], &(dyn Any + Send + Sync)),
+}
+
+impl RolledBack {
+    const fn component<C: Component + Clone>() -> Self {
+        Self {
+            save: save_component::<C>,
+            restore: restore_component::<C>,
+        }
+    }
+
+    const fn resource<R: Resource + Clone>() -> Self {
+        Self {
+            save: save_resource::<R>,
+            restore: restore_resource::<R>,
+        }
+    }
+}
+
+fn save_component<C: Component + Clone>(world: &World, entities: &[Entity]) -> Box<dyn Any + Send + Sync> {
+    let values: Vec<Option<C>> = entities.iter().map(|entity| world.get::<C>(*entity).cloned()).collect();
+    Box::new(values)
+}
+
+fn restore_component<C: Component + Clone>(world: &mut World, entities: &[Entity], saved: &(dyn Any + Send + Sync)) {
+    let Some(values) = saved.downcast_ref::<Vec<Option<C>>>() else {
+        return;
+    };
+
+    for (entity, value) in entities.iter().zip(values) {
+        let Ok(mut entity) = world.get_entity_mut(*entity) else {
+            continue;
+        };
+        match value {
+            Some(value) => {
+                entity.insert(value.clone());
+            }
+            None if entity.contains::<C>() => {
+                entity.remove::<C>();
+            }
+            None => {}
+        }
+    }
+}
+
+fn save_resource<R: Resource + Clone>(world: &World, _: &[Entity]) -> Box<dyn Any + Send + Sync> {
+    Box::new(world.get_resource::<R>().cloned())
+}
+
+fn restore_resource<R: Resource + Clone>(world: &mut World, _: &[Entity], saved: &(dyn Any + Send + Sync)) {
+    match saved.downcast_ref::<Option<R>>() {
+        Some(Some(value)) => world.insert_resource(value.clone()),
+        Some(None) => {
+            world.remove_resource::<R>();
+        }
+        None => {}
+    }
+}
+
+pub struct GameSnapshot {
+    pub tick: u32,
+    pub checksum: u64,
+    entities: Vec<Entity>,
+    state: Vec<Box<dyn Any + Send + Sync>>,
+}
+
+impl GameSnapshot {
+    pub fn save(world: &mut World, tick: u32) -> Self {
+        let mut rollback_query = world.query::<(Entity, &Rollback)>();
+        let mut entities: Vec<(Entity, u32)> = rollback_query
+            .iter(world)
+            .map(|(entity, rollback)| (entity, rollback.0))
+            .collect();
+        entities.sort_by_key(|(_, id)| *id);
+        let entities: Vec<Entity> = entities.into_iter().map(|(entity, _)| entity).collect();
+
+        Self {
+            tick,
+            checksum: checksum(world),
+            state: ROLLED_BACK
+                .iter()
+                .map(|rolled_back| (rolled_back.save)(world, &entities))
+                .collect(),
+            entities,
+        }
+    }
+
+    pub fn restore(&self, world: &mut World) {
+        let mut rollback_query = world.query_filtered::<(Entity, Has<Disabled>), With<Rollback>>();
+        let current: Vec<(Entity, bool)> = rollback_query.iter(world).collect();
+
+        // Entities spawned after the snapshot go away, and entities retired since then come back.
+        for (entity, disabled) in current {
+            if !self.entities.contains(&entity) {
+                world.despawn(entity);
+            } else if disabled {
+                world
+                    .entity_mut(entity)
+                    .remove_recursive::<Children, (Retired, Disabled)>();
+            }
+        }
+
+        for (rolled_back, saved) in ROLLED_BACK.iter().zip(self.state.iter()) {
+            (rolled_back.restore)(world, &self.entities, saved.as_ref());
+        }
+    }
+}
+
+fn checksum(world: &mut World) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    let mut rollback_query = world.query::<(&Rollback, &Transform, Option<&Velocity>)>();
+    let mut entities: Vec<(u32, Vec3, Vec2)> = rollback_query
+        .iter(world)
+        .map(|(rollback, transform, velocity)| {
+            (
+                rollback.0,
+                transform.translation,
+                velocity.map_or(Vec2::ZERO, |velocity| velocity.0),
+            )
+        })
+        .collect();
+    entities.sort_by_key(|(id, ..)| *id);
+    for (id, translation, velocity) in entities {
+        id.hash(&mut hasher);
+        translation.to_array().map(f32::to_bits).hash(&mut hasher);
+        velocity.to_array().map(f32::to_bits).hash(&mut hasher);
+    }
+
+    if let Some(score) = world.get_resource::<Score>() {
+        score.value.hash(&mut hasher);
+        score.players.hash(&mut hasher);
+    }
+    if let Some(enemy_spawn_timer) = world.get_resource::<EnemySpawnTimer>() {
+        enemy_spawn_timer.timer.elapsed().hash(&mut hasher);
+    }
+    if let Some(star_spawn_timer) = world.get_resource::<StarSpawnTimer>() {
+        star_spawn_timer.timer.elapsed().hash(&mut hasher);
+    }
+    if let Some(game_rng) = world.get_resource::<GameRng>() {
+        game_rng.clone().random::<u64>().hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+pub fn retire(commands: &mut Commands, entity: Entity) {
+    commands.entity(entity).queue_handled(retire_entity, warn);
+}
+
+fn retire_entity(mut entity: EntityWorldMut) {
+    let tick = entity.world().get_resource::<RollbackFrame>().map(|frame| frame.tick);
+    match tick {
+        Some(tick) if entity.contains::<Rollback>() => {
+            entity.insert_recursive::<Children>((Retired(tick), Disabled));
+        }
+        _ => entity.despawn(),
+    }
+}
+
+pub fn despawn_retired_before(world: &mut World, tick: u32) {
+    let mut retired_query = world.query_filtered::<(Entity, &Retired), (With<Rollback>, With<Disabled>)>();
+    let expired: Vec<Entity> = retired_query
+        .iter(world)
+        .filter(|(_, retired)| retired.0 < tick)
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for entity in expired {
+        world.despawn(entity);
+    }
+}
+
+fn despawn_retired(mut commands: Commands, retired_query: Query<Entity, (With<Rollback>, With<Disabled>)>) {
+    for entity in retired_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn track_rollback_entity(
+    trigger: Trigger<OnAdd, (Collider, DownedPlayer)>,
+    mut commands: Commands,
+    frame: Option<ResMut<RollbackFrame>>,
+) {
+    if let Some(mut frame) = frame {
+        commands.entity(trigger.target()).try_insert(Rollback(frame.next_id));
+        frame.next_id += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::AppStatePlugin;
+    use crate::game::arena::Arena;
+    use crate::game::coop::{CoopPlugin, Party};
+    use crate::game::enemies::{EnemyPlugin, PlayerHitEvent};
+    use crate::game::level::{Level, StarRules};
+    use crate::game::mode::GameMode;
+    use crate::game::movement::MovementPlugin;
+    use crate::game::mutators::ActiveMutators;
+    use crate::game::obstacles::ObstacleLayout;
+    use crate::game::placement::PlacementPlugin;
+    use crate::game::player::{Controls, Player, PlayerPlugin, RemoteInput};
+    use crate::game::score::ScorePlugin;
+    use crate::game::stars::StarPlugin;
+    use crate::game::state::GameStatePlugin;
+    use bevy::gizmos::GizmoPlugin;
+    use bevy::input::InputPlugin;
+    use bevy::render::render_resource::Shader;
+    use bevy::state::app::StatesPlugin;
+    use std::time::Duration;
+
+    const TICKS: u32 = 90;
+    const TICK_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+    // A co-op match where one player goes down and is revived while golden stars flee from the other.
+    fn coop_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, AssetPlugin::default(), InputPlugin))
+            .init_asset::<Shader>()
+            .add_plugins(GizmoPlugin)
+            .init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .init_asset::<AudioSource>()
+            .add_plugins((
+                AppStatePlugin,
+                GameStatePlugin,
+                RollbackPlugin,
+                PlacementPlugin,
+                MovementPlugin,
+            ))
+            .add_plugins((PlayerPlugin, CoopPlugin, StarPlugin, EnemyPlugin, ScorePlugin))
+            .init_resource::<Arena>()
+            .init_resource::<ObstacleLayout>()
+            .init_resource::<ActiveMutators>()
+            .init_resource::<GameMode>()
+            .insert_resource(Party::Coop)
+            .insert_resource(GameRng::from_seed(21))
+            .insert_resource(RollbackFrame::default())
+            .insert_resource(Level {
+                enemies: Vec::new(),
+                star_count: 0,
+                star_spawn_time: 0.2,
+                star_rules: StarRules {
+                    golden_chance: 1.0,
+                    ..default()
+                },
+                ..default()
+            });
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::Game);
+        app.update();
+
+        let world = app.world_mut();
+        let mut player_query = world.query::<(Entity, &Player, &Transform)>();
+        let players: Vec<(Entity, usize, Vec3)> = player_query
+            .iter(world)
+            .map(|(entity, player, transform)| (entity, player.index, transform.translation))
+            .collect();
+        for (entity, index, _) in players.iter() {
+            let direction = if *index == 0 { Vec2::X } else { Vec2::ZERO };
+            world
+                .entity_mut(*entity)
+                .insert((Controls::Remote, RemoteInput { direction, dash: false }));
+        }
+        let (downed, _, position) = players.iter().find(|(_, index, _)| *index == 1).copied().unwrap();
+        world.trigger(PlayerHitEvent {
+            player: downed,
+            position,
+        });
+        world.flush();
+        app
+    }
+
+    fn simulate(world: &mut World, tick: u32) {
+        let mut time = Time::<()>::default();
+        time.advance_to(TICK_TIME * tick);
+        time.advance_by(TICK_TIME);
+        *world.resource_mut::<Time>() = time;
+
+        let mut frame = world.resource_mut::<RollbackFrame>();
+        frame.tick = tick;
+        frame.simulating = true;
+        world.run_schedule(Update);
+    }
+
+    #[test]
+    fn restored_snapshot_resimulates_to_the_same_checksum() {
+        let mut app = coop_app();
+        let world = app.world_mut();
+        assert_eq!(world.query::<&DownedPlayer>().iter(world).count(), 1);
+
+        let snapshot = GameSnapshot::save(world, 0);
+        let checksums: Vec<u64> = (0..TICKS)
+            .map(|tick| {
+                simulate(world, tick);
+                checksum(world)
+            })
+            .collect();
+        assert_eq!(world.query::<&DownedPlayer>().iter(world).count(), 0);
+        assert_eq!(world.query::<&Player>().iter(world).count(), 2);
+        assert!(world.query::<&GoldenStar>().iter(world).count() > 0);
+
+        snapshot.restore(world);
+        assert_eq!(checksum(world), snapshot.checksum);
+        assert_eq!(world.query::<&DownedPlayer>().iter(world).count(), 1);
+
+        for (tick, expected) in checksums.into_iter().enumerate() {
+            simulate(world, tick as u32);
+            assert_eq!(checksum(world), expected, "Tick {} diverged after the restore", tick);
+        }
+    }
+}
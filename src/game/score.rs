@@ -1,3 +1,9 @@
+use super::level::LevelSet;
+use super::mode::GameMode;
+use super::mutators::ActiveMutators;
+use super::player::{MAX_PLAYERS, Player};
+use super::stars::CollectStarEvent;
+use super::state::running;
 use crate::app_state::AppState;
 use bevy::prelude::*;
 
@@ -6,21 +12,68 @@ pub struct ScorePlugin;
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Score>()
-            .add_systems(OnEnter(AppState::Game), reset_score);
+            .init_resource::<MatchStats>()
+            .add_systems(OnEnter(AppState::Game), reset_score.after(LevelSet))
+            .add_systems(Update, tick_match_time.run_if(running))
+            .add_observer(award_star);
     }
 }
 
-fn reset_score(mut score: ResMut<Score>) {
+fn reset_score(mut score: ResMut<Score>, mut match_stats: ResMut<MatchStats>, mutators: Res<ActiveMutators>) {
     score.value = 0;
+    score.players = [0; MAX_PLAYERS];
+    score.multiplier = mutators.score_multiplier();
+    *match_stats = MatchStats::default();
 }
 
-#[derive(Resource)]
+fn award_star(
+    trigger: Trigger<CollectStarEvent>,
+    game_mode: Res<GameMode>,
+    player_query: Query<&Player>,
+    mut score: ResMut<Score>,
+    mut match_stats: ResMut<MatchStats>,
+) {
+    let event = trigger.event();
+    if *game_mode != GameMode::Survival {
+        score.value += event.value;
+        if let Ok(player) = player_query.get(event.player) {
+            score.players[player.index] += event.value;
+        }
+    }
+    match_stats.stars_collected += 1;
+}
+
+fn tick_match_time(mut match_stats: ResMut<MatchStats>, player_query: Query<(), With<Player>>, time: Res<Time>) {
+    if !player_query.is_empty() {
+        match_stats.seconds += time.delta_secs();
+    }
+}
+
+#[derive(Resource, Clone)]
 pub struct Score {
     pub value: u32,
+    pub players: [u32; MAX_PLAYERS],
+    pub multiplier: f32,
 }
 
 impl Default for Score {
     fn default() -> Self {
-        Self { value: 0 }
+        Self {
+            value: 0,
+            players: [0; MAX_PLAYERS],
+            multiplier: 1.0,
+        }
     }
 }
+
+impl Score {
+    pub fn total(&self) -> u32 {
+        (self.value as f32 * self.multiplier).round() as u32
+    }
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct MatchStats {
+    pub seconds: f32,
+    pub stars_collected: u32,
+}
@@ -1,36 +1,103 @@
-use super::player::{PLAYER_SIZE, Player};
-use super::score::Score;
+use super::arena::{Arena, Wrappable};
+use super::level::Level;
+use super::movement::{Bounded, Collider, MovementSet, Velocity};
+use super::placement::{Placement, PlacementParams, SpawnSet};
+use super::player::{Player, nearest_player};
+use super::rng::GameRng;
+use super::rollback::retire;
 use super::state::running;
 use crate::app_state::AppState;
+use crate::sound::SpatialSounds;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use rand::random;
+use rand::Rng;
+use std::f32::consts::TAU;
 
-const STAR_COUNT: usize = 10;
+pub const STAR_COUNT: usize = 10;
 const STAR_SIZE: f32 = 20.0;
-const STAR_SPAWN_TIME: f32 = 1.0;
+pub const STAR_SPAWN_TIME: f32 = 1.0;
+const STAR_SPACING: f32 = 24.0;
+pub const STAR_LIFETIME: f32 = 12.0;
+pub const MAX_STARS: usize = 25;
+pub const STAR_DRIFT_CHANCE: f32 = 0.2;
+pub const GOLDEN_STAR_CHANCE: f32 = 0.05;
+const STAR_WARNING_TIME: f32 = 3.0;
+const STAR_DRIFT_SPEED: f32 = 60.0;
+const GOLDEN_STAR_VALUE: u32 = 5;
+const GOLDEN_STAR_SCALE: f32 = 1.5;
+const GOLDEN_FLEE_RADIUS: f32 = 220.0;
+const GOLDEN_FLEE_SPEED: f32 = 340.0;
 
 pub struct StarPlugin;
 
 impl Plugin for StarPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<StarSpawnTimer>()
+            .init_resource::<StarAssets>()
             .add_event::<CollectStarEvent>()
-            .add_systems(OnEnter(AppState::Game), spawn_stars)
+            .add_systems(OnEnter(AppState::Game), spawn_stars.in_set(SpawnSet::Stars))
             .add_systems(OnExit(AppState::Game), unspawn_stars)
             .add_systems(
                 Update,
-                (collect_star, tick_spawn_timer, spawn_stars_over_time).run_if(running),
+                (
+                    collect_star.after(MovementSet),
+                    tick_spawn_timer,
+                    spawn_stars_over_time,
+                    age_stars,
+                    flee_player.before(MovementSet),
+                )
+                    .run_if(running),
             );
     }
 }
 
-#[derive(Component)]
-struct Star;
+#[derive(Component, Clone)]
+#[require(
+    Wrappable,
+    Velocity,
+    Bounded = Bounded::Bounce,
+    Collider = Collider {
+        radius: STAR_SIZE / 2.0
+    }
+)]
+pub struct Star {
+    pub value: u32,
+}
 
-#[derive(Resource)]
-struct StarSpawnTimer {
+#[derive(Component, Clone)]
+pub struct StarLifetime {
     timer: Timer,
+    fading: bool,
+}
+
+#[derive(Component, Clone)]
+pub struct GoldenStar;
+
+#[derive(Resource)]
+struct StarAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+    golden_material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for StarAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(RegularPolygon::new(STAR_SIZE / 2.0, 5));
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        let material = materials.add(Color::srgb_u8(190, 243, 84));
+        let golden_material = materials.add(Color::srgb_u8(255, 205, 60));
+        Self {
+            mesh,
+            material,
+            golden_material,
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct StarSpawnTimer {
+    pub timer: Timer,
 }
 
 impl Default for StarSpawnTimer {
@@ -42,34 +109,77 @@ impl Default for StarSpawnTimer {
 }
 
 #[derive(Event)]
-pub struct CollectStarEvent;
+pub struct CollectStarEvent {
+    pub player: Entity,
+    pub position: Vec3,
+    pub value: u32,
+}
 
 fn spawn_star(
     commands: &mut Commands,
-    window: &Window,
-    meshes: &mut ResMut<Assets<Mesh>>,
-    materials: &mut ResMut<Assets<ColorMaterial>>,
+    level: &Level,
+    placement: &mut Placement,
+    game_rng: &mut GameRng,
+    star_assets: &StarAssets,
 ) {
-    let x = random::<f32>() * (window.width() - STAR_SIZE);
-    let y = random::<f32>() * (window.height() - STAR_SIZE);
-
-    commands.spawn((
-        Mesh2d(meshes.add(RegularPolygon::new(STAR_SIZE / 2.0, 5)).into()),
-        MeshMaterial2d(materials.add(Color::srgb_u8(190, 243, 84))),
-        Transform::from_xyz(x, y, 0.0),
-        Star,
+    let golden = game_rng.random::<f32>() < level.star_rules.golden_chance;
+    let (value, material, scale) = if golden {
+        (
+            GOLDEN_STAR_VALUE,
+            star_assets.golden_material.clone(),
+            GOLDEN_STAR_SCALE,
+        )
+    } else {
+        (1, star_assets.material.clone(), 1.0)
+    };
+
+    let regions: Vec<Rect> = level
+        .star_regions
+        .iter()
+        .map(|region| Rect::from_corners(region.min, region.max))
+        .collect();
+    let Some(position) = placement.place(STAR_SIZE * scale / 2.0, STAR_SPACING, &regions, game_rng) else {
+        return;
+    };
+
+    let mut star = commands.spawn((
+        Mesh2d(star_assets.mesh.clone()),
+        MeshMaterial2d(material),
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(scale)),
+        Star { value },
+        Collider {
+            radius: STAR_SIZE * scale / 2.0,
+        },
     ));
+
+    if let Some(lifetime) = level.star_lifetime() {
+        star.insert(StarLifetime {
+            timer: Timer::from_seconds(lifetime, TimerMode::Once),
+            fading: false,
+        });
+    }
+
+    if golden {
+        star.insert(GoldenStar);
+    } else if game_rng.random::<f32>() < level.star_rules.drift_chance {
+        let direction = Vec2::from_angle(game_rng.random::<f32>() * TAU);
+        star.insert(Velocity(direction * STAR_DRIFT_SPEED));
+    }
 }
 
 fn spawn_stars(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    level: Res<Level>,
+    placement: PlacementParams,
+    mut game_rng: ResMut<GameRng>,
+    mut star_spawn_timer: ResMut<StarSpawnTimer>,
+    star_assets: Res<StarAssets>,
 ) {
-    for _ in 0..STAR_COUNT {
-        let window = window_query.single().unwrap();
-        spawn_star(&mut commands, &window, &mut meshes, &mut materials);
+    star_spawn_timer.timer = Timer::from_seconds(level.star_spawn_time, TimerMode::Repeating);
+
+    let mut placement = placement.placement();
+    for _ in 0..level.star_count.min(level.star_rules.max_stars) {
+        spawn_star(&mut commands, &level, &mut placement, &mut game_rng, &star_assets);
     }
 }
 
@@ -81,53 +191,120 @@ fn unspawn_stars(mut commands: Commands, star_query: Query<Entity, With<Star>>)
 
 fn collect_star(
     mut commands: Commands,
-    player_query: Query<&Transform, With<Player>>,
-    star_query: Query<(Entity, &Transform), With<Star>>,
-    asset_server: Res<AssetServer>,
-    mut score: ResMut<Score>,
+    player_query: Query<(Entity, &Transform, &Collider), With<Player>>,
+    star_query: Query<(Entity, &Transform, &Collider, &Star)>,
+    sounds: SpatialSounds,
+    arena: Res<Arena>,
 ) {
-    if let Ok(player_transform) = player_query.single() {
-        for (star_entity, star_transform) in star_query.iter() {
-            let distance = player_transform.translation.distance(star_transform.translation);
+    for (star_entity, star_transform, star_collider, star) in star_query.iter() {
+        let collector = player_query.iter().find(|(_, player_transform, player_collider)| {
+            let distance = arena.distance(player_transform.translation, star_transform.translation);
+            distance < player_collider.radius + star_collider.radius
+        });
 
-            let hit_distance: f32 = PLAYER_SIZE / 2.0 + STAR_SIZE / 2.0;
+        if let Some((player_entity, _, _)) = collector {
+            sounds.play(&mut commands, "audio/laserLarge_000.ogg", star_transform.translation);
 
-            if distance < hit_distance {
-                score.value += 1;
-                let sound_effect = asset_server.load("audio/laserLarge_000.ogg");
+            commands.trigger(CollectStarEvent {
+                player: player_entity,
+                position: star_transform.translation,
+                value: star.value,
+            });
 
-                commands.spawn(AudioPlayer::<AudioSource>(sound_effect));
-
-                // commands.spawn(AudioPlayer {
-                //     settings: PlaybackSettings {
-                //         mode: PlaybackMode::Despawn,
-                //         ..default()
-                //     },
-                //     source: sound_effect,
-                //     ..default()
-                // });
-
-                commands.trigger(CollectStarEvent);
-
-                commands.entity(star_entity).despawn();
-            }
+            retire(&mut commands, star_entity);
         }
     }
 }
 
-fn tick_spawn_timer(mut star_spawn_timer: ResMut<StarSpawnTimer>, time: Res<Time>) {
-    star_spawn_timer.timer.tick(time.delta());
+fn tick_spawn_timer(
+    mut star_spawn_timer: ResMut<StarSpawnTimer>,
+    level: Res<Level>,
+    star_query: Query<(), With<Star>>,
+    time: Res<Time>,
+) {
+    if star_query.iter().count() >= level.star_rules.max_stars {
+        star_spawn_timer.timer.reset();
+    } else {
+        star_spawn_timer.timer.tick(time.delta());
+    }
 }
 
 fn spawn_stars_over_time(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    level: Res<Level>,
+    placement: PlacementParams,
+    mut game_rng: ResMut<GameRng>,
     star_spawn_timer: ResMut<StarSpawnTimer>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    star_assets: Res<StarAssets>,
+) {
+    if level.star_spawn_time > 0.0 && star_spawn_timer.timer.finished() {
+        spawn_star(
+            &mut commands,
+            &level,
+            &mut placement.placement(),
+            &mut game_rng,
+            &star_assets,
+        );
+    }
+}
+
+fn age_stars(
+    mut commands: Commands,
+    mut star_query: Query<(Entity, &mut StarLifetime, &mut MeshMaterial2d<ColorMaterial>)>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
 ) {
-    if star_spawn_timer.timer.finished() {
-        let window = window_query.single().unwrap();
-        spawn_star(&mut commands, &window, &mut meshes, &mut materials);
+    for (entity, mut lifetime, mut material) in star_query.iter_mut() {
+        if lifetime.timer.tick(time.delta()).finished() {
+            retire(&mut commands, entity);
+            continue;
+        }
+
+        let remaining = lifetime.timer.remaining_secs();
+        if remaining > STAR_WARNING_TIME {
+            continue;
+        }
+
+        // Stars share a material until they start fading, so each fading star gets its own copy.
+        if !lifetime.fading {
+            let color = materials
+                .get(&material.0)
+                .map_or(Color::WHITE, |material| material.color);
+            material.0 = materials.add(color);
+            lifetime.fading = true;
+        }
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            let blink = if ((remaining * 8.0) as u32).is_multiple_of(2) {
+                1.0
+            } else {
+                0.6
+            };
+            material.color.set_alpha(remaining / STAR_WARNING_TIME * blink);
+        }
+    }
+}
+
+fn flee_player(
+    mut star_query: Query<(&Transform, &mut Velocity), With<GoldenStar>>,
+    player_query: Query<&Transform, With<Player>>,
+    arena: Res<Arena>,
+) {
+    for (transform, mut velocity) in star_query.iter_mut() {
+        let player_position = nearest_player(
+            &arena,
+            transform.translation,
+            player_query.iter().map(|transform| (transform, ())),
+        )
+        .map(|(transform, _)| transform.translation);
+        let away = player_position
+            .filter(|position| arena.distance(*position, transform.translation) < GOLDEN_FLEE_RADIUS)
+            .and_then(|position| {
+                arena
+                    .delta(position.truncate(), transform.translation.truncate())
+                    .try_normalize()
+            });
+
+        velocity.0 = away.map_or(Vec2::ZERO, |away| away * GOLDEN_FLEE_SPEED);
     }
 }
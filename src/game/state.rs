@@ -1,3 +1,4 @@
+use super::rollback::RollbackFrame;
 use crate::app_state::AppState;
 use bevy::prelude::*;
 
@@ -7,12 +8,23 @@ impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
             .add_systems(OnEnter(AppState::Game), resume)
-            .add_systems(Update, toggle_state.run_if(in_state(AppState::Game)));
+            .add_systems(
+                Update,
+                toggle_state
+                    .run_if(in_state(AppState::Game))
+                    .run_if(not(resource_exists::<RollbackFrame>)),
+            );
     }
 }
 
-pub fn running(game_state: Res<State<GameState>>, app_state: Res<State<AppState>>) -> bool {
-    game_state.eq(&GameState::Running) && app_state.eq(&AppState::Game)
+pub fn running(
+    game_state: Res<State<GameState>>,
+    app_state: Res<State<AppState>>,
+    rollback_frame: Option<Res<RollbackFrame>>,
+) -> bool {
+    game_state.eq(&GameState::Running)
+        && app_state.eq(&AppState::Game)
+        && rollback_frame.is_none_or(|frame| frame.simulating)
 }
 
 pub fn paused(game_state: Res<State<GameState>>, app_state: Res<State<AppState>>) -> bool {
@@ -0,0 +1,60 @@
+use super::enemies::EnemyDifficulty;
+use super::mode::GameMode;
+use super::player::{Player, Shielded};
+use super::score::{MatchStats, Score};
+use super::stars::CollectStarEvent;
+use super::state::running;
+use bevy::prelude::*;
+
+const SURVIVAL_POINTS_PER_SECOND: f32 = 10.0;
+const SURVIVAL_SPEED_RAMP: f32 = 0.01;
+const SURVIVAL_SPAWN_RAMP: f32 = 0.03;
+const SURVIVAL_MAX_SPEED_MULTIPLIER: f32 = 3.0;
+const SHIELD_TIME: f32 = 3.0;
+
+pub struct SurvivalPlugin;
+
+impl Plugin for SurvivalPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (accrue_survival_score, escalate_enemies)
+                .run_if(running)
+                .run_if(survival),
+        )
+        .add_observer(grant_shield);
+    }
+}
+
+pub fn survival(game_mode: Res<GameMode>) -> bool {
+    *game_mode == GameMode::Survival
+}
+
+fn accrue_survival_score(match_stats: Res<MatchStats>, mut score: ResMut<Score>) {
+    let value = (match_stats.seconds * SURVIVAL_POINTS_PER_SECOND) as u32;
+    if score.value != value {
+        score.value = value;
+    }
+}
+
+fn escalate_enemies(match_stats: Res<MatchStats>, mut enemy_difficulty: ResMut<EnemyDifficulty>) {
+    enemy_difficulty.speed_multiplier =
+        (1.0 + match_stats.seconds * SURVIVAL_SPEED_RAMP).min(SURVIVAL_MAX_SPEED_MULTIPLIER);
+    enemy_difficulty.spawn_rate_multiplier = 1.0 + match_stats.seconds * SURVIVAL_SPAWN_RAMP;
+}
+
+fn grant_shield(
+    trigger: Trigger<CollectStarEvent>,
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    player_query: Query<(), With<Player>>,
+) {
+    let player = trigger.event().player;
+    if *game_mode != GameMode::Survival || !player_query.contains(player) {
+        return;
+    }
+
+    commands.entity(player).insert(Shielded {
+        timer: Timer::from_seconds(SHIELD_TIME, TimerMode::Once),
+    });
+}
@@ -0,0 +1,81 @@
+use super::enemies::PlayerHitEvent;
+use super::mode::GameMode;
+use super::player::{Player, Stunned};
+use super::score::Score;
+use super::state::running;
+use crate::app_state::{AppState, TimeUp};
+use bevy::prelude::*;
+
+const TIME_ATTACK_DURATION: f32 = 60.0;
+const VERSUS_DURATION: f32 = 90.0;
+const TIME_ATTACK_HIT_COST: u32 = 3;
+const TIME_ATTACK_STUN_TIME: f32 = 1.0;
+
+pub struct TimeAttackPlugin;
+
+impl Plugin for TimeAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeAttackTimer>()
+            .add_systems(OnEnter(AppState::Game), reset_time_attack_timer)
+            .add_systems(Update, tick_time_attack_timer.run_if(running).run_if(timed_match))
+            .add_observer(stun_player);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct TimeAttackTimer {
+    pub timer: Timer,
+}
+
+impl Default for TimeAttackTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(TIME_ATTACK_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+pub fn timed_match(game_mode: Res<GameMode>) -> bool {
+    matches!(*game_mode, GameMode::TimeAttack | GameMode::Versus)
+}
+
+fn reset_time_attack_timer(mut time_attack_timer: ResMut<TimeAttackTimer>, game_mode: Res<GameMode>) {
+    let duration = match *game_mode {
+        GameMode::Versus => VERSUS_DURATION,
+        _ => TIME_ATTACK_DURATION,
+    };
+    time_attack_timer.timer = Timer::from_seconds(duration, TimerMode::Once);
+}
+
+fn tick_time_attack_timer(
+    mut time_attack_timer: ResMut<TimeAttackTimer>,
+    mut time_up_event_writer: EventWriter<TimeUp>,
+    time: Res<Time>,
+) {
+    if time_attack_timer.timer.tick(time.delta()).just_finished() {
+        time_up_event_writer.write(TimeUp);
+    }
+}
+
+fn stun_player(
+    trigger: Trigger<PlayerHitEvent>,
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut score: ResMut<Score>,
+    player_query: Query<&Player>,
+) {
+    if *game_mode != GameMode::TimeAttack {
+        return;
+    }
+
+    let entity = trigger.event().player;
+    let Ok(player) = player_query.get(entity) else {
+        return;
+    };
+
+    score.value = score.value.saturating_sub(TIME_ATTACK_HIT_COST);
+    score.players[player.index] = score.players[player.index].saturating_sub(TIME_ATTACK_HIT_COST);
+    commands.entity(entity).insert(Stunned {
+        timer: Timer::from_seconds(TIME_ATTACK_STUN_TIME, TimerMode::Once),
+    });
+}
@@ -0,0 +1,72 @@
+use super::enemies::PlayerHitEvent;
+use super::mode::GameMode;
+use super::movement::{Collider, Velocity};
+use super::placement::PlacementParams;
+use super::player::{Player, Shielded};
+use super::rng::GameRng;
+use super::score::Score;
+use bevy::prelude::*;
+
+const VERSUS_HIT_COST: u32 = 5;
+const RESPAWN_SPACING: f32 = 120.0;
+const RESPAWN_SHIELD_TIME: f32 = 2.0;
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(respawn_player);
+    }
+}
+
+type RespawnQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Player,
+        &'static Collider,
+        &'static mut Transform,
+        &'static mut Velocity,
+    ),
+>;
+
+fn respawn_player(
+    trigger: Trigger<PlayerHitEvent>,
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut score: ResMut<Score>,
+    mut game_rng: ResMut<GameRng>,
+    mut params: ParamSet<(PlacementParams, RespawnQuery)>,
+) {
+    if *game_mode != GameMode::Versus {
+        return;
+    }
+
+    let entity = trigger.event().player;
+    let Ok((index, radius)) = params
+        .p1()
+        .get(entity)
+        .map(|(player, collider, _, _)| (player.index, collider.radius))
+    else {
+        return;
+    };
+
+    let position = {
+        let placement_params = params.p0();
+        let mut placement = placement_params.placement();
+        placement
+            .place(radius, RESPAWN_SPACING, &[], &mut game_rng)
+            .unwrap_or_else(|| placement.random_point(radius, &[], &mut game_rng))
+    };
+
+    if let Ok((_, _, mut transform, mut velocity)) = params.p1().get_mut(entity) {
+        transform.translation = position.extend(transform.translation.z);
+        velocity.0 = Vec2::ZERO;
+    }
+
+    score.value = score.value.saturating_sub(VERSUS_HIT_COST);
+    score.players[index] = score.players[index].saturating_sub(VERSUS_HIT_COST);
+    commands.entity(entity).insert(Shielded {
+        timer: Timer::from_seconds(RESPAWN_SHIELD_TIME, TimerMode::Once),
+    });
+}
@@ -0,0 +1,42 @@
+use super::level::{Level, WinCondition};
+use super::player::Player;
+use super::score::{MatchStats, Score};
+use super::stars::Star;
+use super::state::running;
+use crate::app_state::Victory;
+use bevy::prelude::*;
+
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, check_win_condition.run_if(running));
+    }
+}
+
+fn check_win_condition(
+    level: Res<Level>,
+    score: Res<Score>,
+    match_stats: Res<MatchStats>,
+    player_query: Query<(), With<Player>>,
+    star_query: Query<(), With<Star>>,
+    mut victory_event_writer: EventWriter<Victory>,
+) {
+    let Some(win_condition) = level.win_condition else {
+        return;
+    };
+
+    if player_query.is_empty() {
+        return;
+    }
+
+    let won = match win_condition {
+        WinCondition::Score(target) => score.total() >= target,
+        WinCondition::Survive(seconds) => match_stats.seconds >= seconds,
+        WinCondition::AllStars => match_stats.stars_collected > 0 && star_query.is_empty(),
+    };
+
+    if won {
+        victory_event_writer.write(Victory);
+    }
+}
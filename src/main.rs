@@ -1,19 +1,55 @@
 mod app_state;
+mod camera;
+mod editor;
 mod game;
+mod net;
+mod settings;
 mod setup;
+mod sound;
 mod ui;
 
 use app_state::AppStatePlugin;
 use bevy::prelude::*;
+use camera::CameraPlugin;
+use editor::EditorPlugin;
 use game::GamePlugin;
-use setup::SetupPlugin;
+use net::{ClientPlugin, NetRole, PeerPlugin, ServerPlugin, SyncTestPlugin};
+use settings::SettingsPlugin;
+use setup::{HeadlessSetupPlugin, SetupPlugin};
 use ui::UIPlugin;
 
 fn main() {
-    App::new()
-        .add_plugins(SetupPlugin)
-        .add_plugins(AppStatePlugin)
-        .add_plugins(UIPlugin)
-        .add_plugins(GamePlugin)
-        .run();
+    let mut app = App::new();
+    let role = NetRole::from_args();
+
+    if let NetRole::Server(config) = role {
+        app.add_plugins(HeadlessSetupPlugin)
+            .add_plugins(SettingsPlugin)
+            .add_plugins(AppStatePlugin)
+            .add_plugins(GamePlugin)
+            .add_plugins(ServerPlugin(config));
+    } else {
+        app.add_plugins(SetupPlugin)
+            .add_plugins(SettingsPlugin)
+            .add_plugins(CameraPlugin)
+            .add_plugins(AppStatePlugin)
+            .add_plugins(UIPlugin)
+            .add_plugins(GamePlugin)
+            .add_plugins(EditorPlugin);
+    }
+
+    match role {
+        NetRole::Client(config) => {
+            app.add_plugins(ClientPlugin(config));
+        }
+        NetRole::Peer(config, peer) => {
+            app.add_plugins(PeerPlugin(config, peer));
+        }
+        NetRole::SyncTest(distance) => {
+            app.add_plugins(SyncTestPlugin(distance));
+        }
+        NetRole::Offline | NetRole::Server(_) => {}
+    }
+
+    app.run();
 }
@@ -0,0 +1,72 @@
+mod client;
+mod peer;
+mod protocol;
+mod server;
+mod transport;
+
+use bevy::prelude::*;
+pub use client::ClientPlugin;
+pub use peer::{PeerPlugin, SyncTestPlugin};
+pub use server::ServerPlugin;
+use std::net::SocketAddr;
+use std::time::Duration;
+use transport::LinkConditioner;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:5000";
+const DEFAULT_SYNC_TEST_DISTANCE: u32 = 2;
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct NetConfig {
+    pub address: SocketAddr,
+    pub conditioner: LinkConditioner,
+}
+
+#[derive(Clone, Copy)]
+pub enum NetRole {
+    Offline,
+    Server(NetConfig),
+    Client(NetConfig),
+    Peer(NetConfig, SocketAddr),
+    SyncTest(u32),
+}
+
+impl NetRole {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let value = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .and_then(|index| args.get(index + 1))
+                .filter(|value| !value.starts_with("--"))
+        };
+        let number = |flag: &str| value(flag).and_then(|value| value.parse::<f32>().ok());
+
+        let conditioner = LinkConditioner {
+            latency: Duration::from_secs_f32(number("--latency").unwrap_or(0.0).max(0.0) / 1000.0),
+            jitter: Duration::from_secs_f32(number("--jitter").unwrap_or(0.0).max(0.0) / 1000.0),
+            loss: (number("--loss").unwrap_or(0.0) / 100.0).clamp(0.0, 1.0),
+        };
+        let config = |flag: &str| NetConfig {
+            address: value(flag)
+                .and_then(|address| address.parse().ok())
+                .unwrap_or_else(|| DEFAULT_ADDRESS.parse().unwrap()),
+            conditioner,
+        };
+
+        if args.iter().any(|arg| arg == "--server") {
+            NetRole::Server(config("--server"))
+        } else if args.iter().any(|arg| arg == "--connect") {
+            NetRole::Client(config("--connect"))
+        } else if let Some(peer) = value("--peer").and_then(|address| address.parse().ok()) {
+            NetRole::Peer(config("--bind"), peer)
+        } else if args.iter().any(|arg| arg == "--sync-test") {
+            NetRole::SyncTest(
+                value("--sync-test")
+                    .and_then(|distance| distance.parse().ok())
+                    .unwrap_or(DEFAULT_SYNC_TEST_DISTANCE),
+            )
+        } else {
+            NetRole::Offline
+        }
+    }
+}
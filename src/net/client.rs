@@ -0,0 +1,368 @@
+use super::NetConfig;
+use super::protocol::{ClientMessage, EntityKind, InputFrame, ServerMessage, Snapshot};
+use super::transport::Transport;
+use crate::app_state::AppState;
+use crate::game::{Arena, ArenaBoundary, Controls, PLAYER_COLORS};
+use crate::settings::Settings;
+use crate::ui::{box_node, hub_node, text_bundle};
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+
+const HELLO_INTERVAL: f32 = 0.5;
+const INTERPOLATION_DELAY: f32 = 0.1;
+const CLOCK_SMOOTHING: f32 = 0.1;
+const SNAPSHOT_BUFFER_SIZE: usize = 32;
+const ENEMY_COLOR: Color = Color::srgb_u8(222, 84, 84);
+const STAR_COLOR: Color = Color::srgb_u8(190, 243, 84);
+const OTHER_COLOR: Color = Color::srgb_u8(255, 224, 96);
+
+pub struct ClientPlugin(pub NetConfig);
+
+impl Plugin for ClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.0)
+            .init_resource::<ProxyAssets>()
+            .add_systems(Startup, connect_to_server)
+            .add_systems(OnEnter(AppState::Online), spawn_online_hub)
+            .add_systems(OnExit(AppState::Online), (leave_server, despawn_online_entities))
+            .add_systems(
+                Update,
+                (
+                    receive_server_messages,
+                    send_hello,
+                    send_input,
+                    interpolate_snapshots,
+                    update_online_hub,
+                    flush_client,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Online))
+                    .run_if(resource_exists::<Connection>),
+            );
+    }
+}
+
+#[derive(Resource)]
+struct Connection {
+    transport: Transport,
+    server: SocketAddr,
+    player: Option<u8>,
+    hello_timer: Timer,
+    input: InputFrame,
+    clock_offset: Option<f32>,
+    snapshots: VecDeque<Snapshot>,
+    proxies: HashMap<u64, Entity>,
+    full: bool,
+}
+
+#[derive(Component)]
+struct Proxy;
+
+#[derive(Component)]
+struct OnlineHub;
+
+#[derive(Component)]
+struct OnlineStatus;
+
+#[derive(Resource)]
+struct ProxyAssets {
+    circle: Handle<Mesh>,
+    star: Handle<Mesh>,
+    player_materials: Vec<Handle<ColorMaterial>>,
+    enemy_material: Handle<ColorMaterial>,
+    star_material: Handle<ColorMaterial>,
+    other_material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for ProxyAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let circle = meshes.add(Circle::new(1.0));
+        let star = meshes.add(RegularPolygon::new(1.0, 5));
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        Self {
+            circle,
+            star,
+            player_materials: PLAYER_COLORS.iter().map(|color| materials.add(*color)).collect(),
+            enemy_material: materials.add(ENEMY_COLOR),
+            star_material: materials.add(STAR_COLOR),
+            other_material: materials.add(OTHER_COLOR),
+        }
+    }
+}
+
+impl ProxyAssets {
+    fn appearance(&self, kind: EntityKind) -> (Handle<Mesh>, Handle<ColorMaterial>) {
+        match kind {
+            EntityKind::Player(index) => (
+                self.circle.clone(),
+                self.player_materials
+                    .get(index as usize)
+                    .unwrap_or(&self.other_material)
+                    .clone(),
+            ),
+            EntityKind::Enemy => (self.circle.clone(), self.enemy_material.clone()),
+            EntityKind::Star => (self.star.clone(), self.star_material.clone()),
+            EntityKind::Other => (self.circle.clone(), self.other_material.clone()),
+        }
+    }
+}
+
+fn connect_to_server(
+    mut commands: Commands,
+    config: Res<NetConfig>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut app_exit_event_writer: EventWriter<AppExit>,
+) {
+    let local_address = SocketAddr::new(
+        if config.address.is_ipv4() {
+            [0, 0, 0, 0].into()
+        } else {
+            [0u16; 8].into()
+        },
+        0,
+    );
+
+    match Transport::bind(local_address, config.conditioner) {
+        Ok(transport) => {
+            info!("Connecting to {} from {:?}", config.address, transport.local_address());
+            commands.insert_resource(Connection {
+                transport,
+                server: config.address,
+                player: None,
+                hello_timer: Timer::from_seconds(HELLO_INTERVAL, TimerMode::Repeating),
+                input: InputFrame::default(),
+                clock_offset: None,
+                snapshots: VecDeque::new(),
+                proxies: HashMap::new(),
+                full: false,
+            });
+            next_app_state.set(AppState::Online);
+        }
+        Err(error) => {
+            error!("Failed to open a client socket: {}", error);
+            app_exit_event_writer.write(AppExit::error());
+        }
+    }
+}
+
+fn receive_server_messages(mut connection: ResMut<Connection>, mut arena: ResMut<Arena>, time: Res<Time<Real>>) {
+    let now = time.elapsed_secs();
+
+    for (address, bytes) in connection.transport.receive() {
+        if address != connection.server {
+            continue;
+        }
+
+        match ServerMessage::decode(&bytes) {
+            Some(ServerMessage::Welcome {
+                player,
+                arena_size,
+                wraps,
+            }) => {
+                if connection.player.is_none() {
+                    info!("Joined as player {}", player + 1);
+                }
+                connection.player = Some(player);
+                connection.full = false;
+                arena.width = arena_size.x;
+                arena.height = arena_size.y;
+                arena.boundary = if wraps {
+                    ArenaBoundary::Wrap
+                } else {
+                    ArenaBoundary::Walls
+                };
+            }
+            Some(ServerMessage::Full) => connection.full = true,
+            Some(ServerMessage::Snapshot(snapshot)) => {
+                if connection
+                    .snapshots
+                    .back()
+                    .is_some_and(|latest| (snapshot.tick.wrapping_sub(latest.tick) as i32) <= 0)
+                {
+                    continue;
+                }
+
+                let offset = snapshot.time - now;
+                connection.clock_offset = Some(match connection.clock_offset {
+                    Some(clock_offset) => clock_offset.lerp(offset, CLOCK_SMOOTHING),
+                    None => offset,
+                });
+                connection.snapshots.push_back(snapshot);
+                if connection.snapshots.len() > SNAPSHOT_BUFFER_SIZE {
+                    connection.snapshots.pop_front();
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+fn send_hello(mut connection: ResMut<Connection>, time: Res<Time<Real>>) {
+    if connection.player.is_some() || !connection.hello_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let server = connection.server;
+    connection.transport.send(server, ClientMessage::Hello.encode());
+}
+
+fn send_input(
+    mut connection: ResMut<Connection>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    settings: Res<Settings>,
+) {
+    if connection.player.is_none() {
+        return;
+    }
+
+    let controls = Controls::Shared;
+    let input = &mut connection.input;
+    input.sequence = input.sequence.wrapping_add(1);
+    input.direction = controls.direction(&keys, &gamepads);
+    if controls.dash_key(&settings).is_some_and(|key| keys.just_pressed(key)) {
+        input.dashes = input.dashes.wrapping_add(1);
+    }
+
+    let (server, input) = (connection.server, connection.input);
+    connection.transport.send(server, ClientMessage::Input(input).encode());
+}
+
+fn interpolate_snapshots(
+    mut commands: Commands,
+    mut connection: ResMut<Connection>,
+    mut proxy_query: Query<&mut Transform, With<Proxy>>,
+    proxy_assets: Res<ProxyAssets>,
+    arena: Res<Arena>,
+    time: Res<Time<Real>>,
+) {
+    let Some(clock_offset) = connection.clock_offset else {
+        return;
+    };
+    let render_time = time.elapsed_secs() + clock_offset - INTERPOLATION_DELAY;
+
+    let snapshots = &connection.snapshots;
+    let Some(newer_index) = snapshots
+        .iter()
+        .position(|snapshot| snapshot.time >= render_time)
+        .or(snapshots.len().checked_sub(1))
+    else {
+        return;
+    };
+    let newer = &snapshots[newer_index];
+    let older = newer_index.checked_sub(1).map(|index| &snapshots[index]);
+    let t = older.map_or(1.0, |older| {
+        ((render_time - older.time) / (newer.time - older.time).max(f32::EPSILON)).clamp(0.0, 1.0)
+    });
+
+    let mut proxies = HashMap::new();
+    for state in newer.entities.iter() {
+        let position = older
+            .and_then(|older| {
+                older
+                    .entities
+                    .iter()
+                    .find(|previous| previous.id == state.id && previous.kind == state.kind)
+            })
+            .map_or(state.position, |previous| {
+                previous.position + arena.delta(previous.position, state.position) * t
+            });
+        let transform = Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(state.radius));
+
+        let existing = connection
+            .proxies
+            .get(&state.id)
+            .copied()
+            .filter(|entity| proxy_query.contains(*entity));
+        let entity = match existing {
+            Some(entity) => {
+                if let Ok(mut proxy_transform) = proxy_query.get_mut(entity) {
+                    *proxy_transform = transform;
+                }
+                entity
+            }
+            None => {
+                let (mesh, material) = proxy_assets.appearance(state.kind);
+                commands
+                    .spawn((Proxy, Mesh2d(mesh), MeshMaterial2d(material), transform))
+                    .id()
+            }
+        };
+        proxies.insert(state.id, entity);
+    }
+
+    for (id, entity) in connection.proxies.iter() {
+        if !proxies.contains_key(id) {
+            commands.entity(*entity).despawn();
+        }
+    }
+    connection.proxies = proxies;
+}
+
+fn spawn_online_hub(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        OnlineHub,
+        hub_node(),
+        children![(
+            Node {
+                width: Val::Auto,
+                ..box_node()
+            },
+            children![(OnlineStatus, text_bundle(&asset_server, "Connecting...", 32.0))]
+        )],
+    ));
+}
+
+fn update_online_hub(connection: Res<Connection>, mut status_query: Query<&mut Text, With<OnlineStatus>>) {
+    let status = match (connection.player, connection.snapshots.back()) {
+        (Some(player), Some(snapshot)) => {
+            let scores: Vec<String> = snapshot
+                .scores
+                .iter()
+                .enumerate()
+                .map(|(index, score)| format!("P{}: {}", index + 1, score))
+                .collect();
+            format!("You are P{}    {}", player + 1, scores.join("    "))
+        }
+        (Some(player), None) => format!("You are P{}, waiting for the match", player + 1),
+        (None, _) if connection.full => "Server is full".to_string(),
+        (None, _) => format!("Connecting to {}...", connection.server),
+    };
+
+    for mut text in status_query.iter_mut() {
+        if **text != status {
+            **text = status.clone();
+        }
+    }
+}
+
+fn flush_client(mut connection: ResMut<Connection>) {
+    connection.transport.flush();
+}
+
+fn leave_server(connection: Option<ResMut<Connection>>) {
+    if let Some(mut connection) = connection {
+        let server = connection.server;
+        connection.transport.send(server, ClientMessage::Goodbye.encode());
+        connection.transport.flush();
+        connection.player = None;
+    }
+}
+
+fn despawn_online_entities(
+    mut commands: Commands,
+    proxy_query: Query<Entity, With<Proxy>>,
+    hub_query: Query<Entity, With<OnlineHub>>,
+    connection: Option<ResMut<Connection>>,
+) {
+    for entity in proxy_query.iter().chain(hub_query.iter()) {
+        commands.entity(entity).despawn();
+    }
+    if let Some(mut connection) = connection {
+        connection.proxies.clear();
+        connection.snapshots.clear();
+        connection.clock_offset = None;
+    }
+}
@@ -0,0 +1,579 @@
+use super::NetConfig;
+use super::protocol::{PeerInput, PeerMessage};
+use super::transport::Transport;
+use crate::app_state::{AppState, cancel_game_over_timer};
+use crate::game::{
+    Controls, GameMode, GameSnapshot, MatchSeed, Party, Player, RemoteInput, RollbackFrame, SelectedLevel,
+    SelectedMutators, despawn_retired_before,
+};
+use crate::settings::Settings;
+use crate::ui::{box_node, hub_node, text_bundle};
+use bevy::ecs::schedule::{ExecutorKind, ScheduleLabel};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use rand::random;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+const TICK_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+const MAX_TICKS_PER_FRAME: u32 = 4;
+const MAX_PREDICTION: u32 = 8;
+const MAX_INPUTS_PER_MESSAGE: usize = 64;
+const HELLO_INTERVAL: f32 = 0.5;
+const PEER_TIMEOUT: f32 = 5.0;
+const CHECKSUM_INTERVAL: u32 = 30;
+const CHECKSUM_HISTORY: usize = 32;
+
+pub struct PeerPlugin(pub NetConfig, pub SocketAddr);
+
+impl Plugin for PeerPlugin {
+    fn build(&self, app: &mut App) {
+        add_rollback_systems(app);
+        app.insert_resource(self.0)
+            .insert_resource(PeerAddress(self.1))
+            .add_systems(Startup, open_peer_link)
+            .add_systems(OnEnter(AppState::Online), spawn_lobby)
+            .add_systems(OnExit(AppState::Online), despawn_lobby)
+            .add_systems(OnEnter(AppState::Game), start_peer_session)
+            .add_systems(OnExit(AppState::Game), end_peer_session)
+            .add_systems(
+                Update,
+                (exchange_hellos, update_lobby)
+                    .chain()
+                    .run_if(in_state(AppState::Online))
+                    .run_if(resource_exists::<PeerLink>),
+            );
+    }
+}
+
+pub struct SyncTestPlugin(pub u32);
+
+impl Plugin for SyncTestPlugin {
+    fn build(&self, app: &mut App) {
+        add_rollback_systems(app);
+        app.insert_resource(SyncTest { distance: self.0 })
+            .init_resource::<RollbackFrame>()
+            .add_systems(OnEnter(AppState::Game), start_sync_test_session)
+            .add_systems(OnExit(AppState::Game), end_sync_test_session);
+    }
+}
+
+fn add_rollback_systems(app: &mut App) {
+    // The simulation has to run its systems in the same order on every peer and on every resimulation.
+    for schedule in [Update.intern(), OnEnter(AppState::Game).intern()] {
+        app.edit_schedule(schedule, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+    }
+
+    app.add_systems(
+        PreUpdate,
+        (sample_local_input, advance_rollback)
+            .chain()
+            .after(InputSystem)
+            .run_if(in_state(AppState::Game))
+            .run_if(resource_exists::<RollbackSession>),
+    )
+    .add_observer(drive_player_from_session);
+}
+
+#[derive(Resource, Clone, Copy)]
+struct PeerAddress(SocketAddr);
+
+#[derive(Resource, Clone, Copy)]
+struct SyncTest {
+    distance: u32,
+}
+
+#[derive(Resource)]
+struct PeerLink {
+    transport: Transport,
+    peer: SocketAddr,
+    local_seed: u64,
+    remote_seed: Option<u64>,
+    hello_timer: Timer,
+    heard_inputs: bool,
+    last_heard: f32,
+    remote_ack: u32,
+    last_checksum_tick: u32,
+    checksums: VecDeque<(u32, u64)>,
+    remote_checksums: VecDeque<(u32, u64)>,
+}
+
+impl PeerLink {
+    fn local_player(&self) -> usize {
+        match self.remote_seed {
+            Some(remote_seed) if remote_seed < self.local_seed => 1,
+            _ => 0,
+        }
+    }
+
+    fn send(&mut self, message: PeerMessage) {
+        let peer = self.peer;
+        self.transport.send(peer, message.encode());
+    }
+
+    fn compare_checksums(&self, tick: u32) {
+        let local = self.checksums.iter().find(|(local_tick, _)| *local_tick == tick);
+        let remote = self
+            .remote_checksums
+            .iter()
+            .find(|(remote_tick, _)| *remote_tick == tick);
+        if let (Some((_, local)), Some((_, remote))) = (local, remote)
+            && local != remote
+        {
+            error!("Desync with peer at tick {}: {:016x} != {:016x}", tick, local, remote);
+        }
+    }
+}
+
+#[derive(Default)]
+struct InputHistory {
+    inputs: Vec<PeerInput>,
+}
+
+impl InputHistory {
+    fn confirmed(&self) -> u32 {
+        self.inputs.len() as u32
+    }
+
+    fn predict(&self) -> PeerInput {
+        self.inputs.last().map_or_else(PeerInput::default, |input| PeerInput {
+            direction: input.direction,
+            dash: false,
+        })
+    }
+
+    fn get(&self, tick: u32) -> PeerInput {
+        self.inputs
+            .get(tick as usize)
+            .copied()
+            .unwrap_or_else(|| self.predict())
+    }
+}
+
+struct LocalInput {
+    player: usize,
+    controls: Controls,
+    direction: Vec2,
+    dash: bool,
+}
+
+#[derive(Resource)]
+struct RollbackSession {
+    tick: u32,
+    accumulator: Duration,
+    players: Vec<InputHistory>,
+    local: Vec<LocalInput>,
+    snapshots: VecDeque<GameSnapshot>,
+    sync_test: Option<SyncTest>,
+}
+
+impl RollbackSession {
+    fn new(count: usize, local: impl IntoIterator<Item = (usize, Controls)>, sync_test: Option<SyncTest>) -> Self {
+        Self {
+            tick: 0,
+            accumulator: Duration::ZERO,
+            players: (0..count).map(|_| InputHistory::default()).collect(),
+            local: local
+                .into_iter()
+                .map(|(player, controls)| LocalInput {
+                    player,
+                    controls,
+                    direction: Vec2::ZERO,
+                    dash: false,
+                })
+                .collect(),
+            snapshots: VecDeque::new(),
+            sync_test,
+        }
+    }
+
+    fn record_local_input(&mut self) {
+        for local in self.local.iter_mut() {
+            self.players[local.player].inputs.push(PeerInput {
+                direction: local.direction,
+                dash: std::mem::take(&mut local.dash),
+            });
+        }
+    }
+
+    fn remote_confirmed(&self) -> u32 {
+        (0..self.players.len())
+            .filter(|player| self.local.iter().all(|local| local.player != *player))
+            .map(|player| self.players[player].confirmed())
+            .min()
+            .unwrap_or(self.tick)
+    }
+
+    fn save(&mut self, world: &mut World, tick: u32) {
+        let snapshot = GameSnapshot::save(world, tick);
+        match self.snapshots.iter_mut().find(|saved| saved.tick == tick) {
+            Some(saved) => {
+                if self.sync_test.is_some() {
+                    assert_eq!(
+                        saved.checksum, snapshot.checksum,
+                        "Sync test failed: resimulating tick {} gave a different result",
+                        tick
+                    );
+                }
+                *saved = snapshot;
+            }
+            None => self.snapshots.push_back(snapshot),
+        }
+    }
+
+    fn simulate(&mut self, world: &mut World, tick: u32) {
+        let mut player_query = world.query::<(&Player, &mut RemoteInput)>();
+        for (player, mut remote) in player_query.iter_mut(world) {
+            let input = self.players[player.index].get(tick);
+            remote.direction = input.direction;
+            remote.dash = input.dash;
+        }
+
+        let mut time = Time::<()>::default();
+        time.advance_to(TICK_TIME * tick);
+        time.advance_by(TICK_TIME);
+        *world.resource_mut::<Time>() = time;
+
+        let mut frame = world.resource_mut::<RollbackFrame>();
+        frame.tick = tick;
+        frame.simulating = true;
+        frame.replaying = tick < self.tick;
+        world.run_schedule(Update);
+
+        self.save(world, tick + 1);
+    }
+
+    fn roll_back(&mut self, world: &mut World, from: u32) {
+        let Some(snapshot) = self.snapshots.iter().find(|snapshot| snapshot.tick == from) else {
+            warn!("Cannot roll back to tick {}: no snapshot", from);
+            return;
+        };
+        snapshot.restore(world);
+
+        for tick in from..self.tick {
+            self.simulate(world, tick);
+        }
+
+        // A game over predicted from the wrong inputs must not outlive the correction.
+        let mut player_query = world.query_filtered::<(), With<Player>>();
+        if player_query.iter(world).next().is_some()
+            && let Err(error) = world.run_system_cached(cancel_game_over_timer)
+        {
+            warn!("Failed to cancel the game over: {}", error);
+        }
+    }
+}
+
+fn open_peer_link(
+    mut commands: Commands,
+    config: Res<NetConfig>,
+    peer_address: Res<PeerAddress>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut app_exit_event_writer: EventWriter<AppExit>,
+) {
+    match Transport::bind(config.address, config.conditioner) {
+        Ok(transport) => {
+            info!("Waiting for {} on {}", peer_address.0, config.address);
+            commands.insert_resource(PeerLink {
+                transport,
+                peer: peer_address.0,
+                local_seed: random(),
+                remote_seed: None,
+                hello_timer: Timer::from_seconds(HELLO_INTERVAL, TimerMode::Repeating),
+                heard_inputs: false,
+                last_heard: 0.0,
+                remote_ack: 0,
+                last_checksum_tick: 0,
+                checksums: VecDeque::new(),
+                remote_checksums: VecDeque::new(),
+            });
+            commands.insert_resource(Party::Coop);
+            commands.insert_resource(GameMode::Classic);
+            commands.insert_resource(SelectedLevel::Endless);
+            commands.insert_resource(SelectedMutators::default());
+            next_app_state.set(AppState::Online);
+        }
+        Err(error) => {
+            error!("Failed to listen on {}: {}", config.address, error);
+            app_exit_event_writer.write(AppExit::error());
+        }
+    }
+}
+
+fn exchange_hellos(
+    mut commands: Commands,
+    mut link: ResMut<PeerLink>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    time: Res<Time<Real>>,
+) {
+    let peer = link.peer;
+    for (address, bytes) in link.transport.receive() {
+        if address == peer
+            && let Some(PeerMessage::Hello { seed }) = PeerMessage::decode(&bytes)
+        {
+            link.remote_seed = Some(seed);
+        }
+    }
+
+    if link.hello_timer.tick(time.delta()).just_finished() {
+        let seed = link.local_seed;
+        link.send(PeerMessage::Hello { seed });
+    }
+    link.transport.flush();
+
+    if let Some(remote_seed) = link.remote_seed {
+        info!("Connected to {} as player {}", peer, link.local_player() + 1);
+        link.last_heard = time.elapsed_secs();
+        commands.insert_resource(MatchSeed(link.local_seed ^ remote_seed));
+        commands.insert_resource(RollbackFrame::default());
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn start_peer_session(mut commands: Commands, link: Option<Res<PeerLink>>) {
+    if let Some(link) = link {
+        commands.insert_resource(RollbackSession::new(2, [(link.local_player(), Controls::Shared)], None));
+    }
+}
+
+fn end_peer_session(mut commands: Commands) {
+    commands.remove_resource::<RollbackSession>();
+    commands.remove_resource::<RollbackFrame>();
+    commands.remove_resource::<MatchSeed>();
+    commands.remove_resource::<PeerLink>();
+}
+
+fn start_sync_test_session(
+    mut commands: Commands,
+    sync_test: Res<SyncTest>,
+    party: Res<Party>,
+    game_mode: Res<GameMode>,
+) {
+    let count = party.player_count(*game_mode);
+    let local = (0..count).map(|index| (index, Controls::for_player(count, index)));
+    commands.insert_resource(RollbackSession::new(count, local, Some(*sync_test)));
+}
+
+fn end_sync_test_session(mut commands: Commands) {
+    commands.remove_resource::<RollbackSession>();
+    commands.insert_resource(RollbackFrame::default());
+}
+
+fn drive_player_from_session(
+    trigger: Trigger<OnAdd, Player>,
+    mut commands: Commands,
+    frame: Option<Res<RollbackFrame>>,
+) {
+    if frame.is_some() {
+        commands
+            .entity(trigger.target())
+            .insert((Controls::Remote, RemoteInput::default()));
+    }
+}
+
+fn sample_local_input(
+    mut session: ResMut<RollbackSession>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    settings: Res<Settings>,
+) {
+    for local in session.local.iter_mut() {
+        local.direction = local.controls.direction(&keys, &gamepads);
+        local.dash |= local
+            .controls
+            .dash_key(&settings)
+            .is_some_and(|key| keys.just_pressed(key));
+    }
+}
+
+fn advance_rollback(world: &mut World) {
+    let Some(mut session) = world.remove_resource::<RollbackSession>() else {
+        return;
+    };
+    let mut link = world.remove_resource::<PeerLink>();
+    let frame_time = *world.resource::<Time>();
+    let real_time = *world.resource::<Time<Real>>();
+
+    if session.snapshots.is_empty() {
+        session.save(world, 0);
+    }
+
+    let mispredicted = match link.as_mut() {
+        Some(link) => receive_peer_messages(link, &mut session, real_time.elapsed_secs()),
+        None => None,
+    };
+    let rollback_to = match session.sync_test {
+        Some(sync_test) => session.tick.checked_sub(sync_test.distance),
+        None => mispredicted,
+    };
+    if let Some(from) = rollback_to.filter(|from| *from < session.tick) {
+        session.roll_back(world, from);
+    }
+
+    session.accumulator += real_time.delta();
+    for _ in 0..MAX_TICKS_PER_FRAME {
+        if session.accumulator < TICK_TIME
+            || link.is_some() && session.tick >= session.remote_confirmed() + MAX_PREDICTION
+        {
+            break;
+        }
+
+        session.accumulator -= TICK_TIME;
+        session.record_local_input();
+        let tick = session.tick;
+        session.simulate(world, tick);
+        session.tick += 1;
+    }
+    session.accumulator = session.accumulator.min(TICK_TIME * MAX_TICKS_PER_FRAME);
+
+    let oldest = match (&link, session.sync_test) {
+        (_, Some(sync_test)) => session.tick.saturating_sub(sync_test.distance),
+        (Some(_), None) => session.remote_confirmed().min(session.tick),
+        (None, None) => session.tick,
+    };
+    if let Some(link) = link.as_mut() {
+        send_peer_messages(link, &session, oldest);
+    }
+    session.snapshots.retain(|snapshot| snapshot.tick >= oldest);
+    despawn_retired_before(world, oldest);
+
+    let mut frame = world.resource_mut::<RollbackFrame>();
+    frame.simulating = false;
+    frame.replaying = false;
+    *world.resource_mut::<Time>() = frame_time;
+
+    let timed_out = link
+        .as_ref()
+        .is_some_and(|link| real_time.elapsed_secs() - link.last_heard > PEER_TIMEOUT);
+    if timed_out {
+        warn!("Lost the connection to the peer");
+        world.resource_mut::<NextState<AppState>>().set(AppState::MainMenu);
+    }
+
+    world.insert_resource(session);
+    if let Some(link) = link {
+        world.insert_resource(link);
+    }
+}
+
+fn receive_peer_messages(link: &mut PeerLink, session: &mut RollbackSession, now: f32) -> Option<u32> {
+    let remote_player = 1 - link.local_player();
+    let prediction = session.players[remote_player].predict();
+    let mut mispredicted = None;
+
+    for (address, bytes) in link.transport.receive() {
+        if address != link.peer {
+            continue;
+        }
+        link.last_heard = now;
+
+        match PeerMessage::decode(&bytes) {
+            Some(PeerMessage::Inputs { ack, start, inputs }) => {
+                link.heard_inputs = true;
+                link.remote_ack = link.remote_ack.max(ack);
+
+                let history = &mut session.players[remote_player];
+                if start > history.confirmed() {
+                    continue;
+                }
+                for (tick, input) in (start..).zip(inputs) {
+                    if tick < history.confirmed() {
+                        continue;
+                    }
+                    if tick < session.tick && input != prediction && mispredicted.is_none() {
+                        mispredicted = Some(tick);
+                    }
+                    history.inputs.push(input);
+                }
+            }
+            Some(PeerMessage::Checksum { tick, checksum }) => {
+                link.remote_checksums.push_back((tick, checksum));
+                if link.remote_checksums.len() > CHECKSUM_HISTORY {
+                    link.remote_checksums.pop_front();
+                }
+                link.compare_checksums(tick);
+            }
+            Some(PeerMessage::Hello { .. }) | None => {}
+        }
+    }
+
+    mispredicted
+}
+
+fn send_peer_messages(link: &mut PeerLink, session: &RollbackSession, confirmed: u32) {
+    if !link.heard_inputs {
+        let seed = link.local_seed;
+        link.send(PeerMessage::Hello { seed });
+    }
+
+    let local_player = link.local_player();
+    let inputs = &session.players[local_player].inputs;
+    let start = (link.remote_ack as usize).min(inputs.len());
+    let end = (start + MAX_INPUTS_PER_MESSAGE).min(inputs.len());
+    link.send(PeerMessage::Inputs {
+        ack: session.players[1 - local_player].confirmed(),
+        start: start as u32,
+        inputs: inputs[start..end].to_vec(),
+    });
+
+    // Snapshots up to the last confirmed tick can no longer change, so both peers must agree on them.
+    let final_snapshots: Vec<(u32, u64)> = session
+        .snapshots
+        .iter()
+        .filter(|snapshot| {
+            snapshot.tick <= confirmed
+                && snapshot.tick > link.last_checksum_tick
+                && snapshot.tick.is_multiple_of(CHECKSUM_INTERVAL)
+        })
+        .map(|snapshot| (snapshot.tick, snapshot.checksum))
+        .collect();
+    for (tick, checksum) in final_snapshots {
+        link.last_checksum_tick = tick;
+        link.checksums.push_back((tick, checksum));
+        if link.checksums.len() > CHECKSUM_HISTORY {
+            link.checksums.pop_front();
+        }
+        link.compare_checksums(tick);
+        link.send(PeerMessage::Checksum { tick, checksum });
+    }
+
+    link.transport.flush();
+}
+
+#[derive(Component)]
+struct Lobby;
+
+#[derive(Component)]
+struct LobbyStatus;
+
+fn spawn_lobby(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Lobby,
+        hub_node(),
+        children![(
+            Node {
+                width: Val::Auto,
+                ..box_node()
+            },
+            children![(LobbyStatus, text_bundle(&asset_server, "Waiting for peer...", 32.0))]
+        )],
+    ));
+}
+
+fn update_lobby(link: Res<PeerLink>, mut status_query: Query<&mut Text, With<LobbyStatus>>) {
+    let status = format!("Waiting for {}...", link.peer);
+    for mut text in status_query.iter_mut() {
+        if **text != status {
+            **text = status.clone();
+        }
+    }
+}
+
+fn despawn_lobby(mut commands: Commands, lobby_query: Query<Entity, With<Lobby>>) {
+    for entity in lobby_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,505 @@
+use crate::game::MAX_PLAYERS;
+use bevy::prelude::*;
+
+const PROTOCOL_ID: u16 = 0xba11;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InputFrame {
+    pub sequence: u32,
+    pub direction: Vec2,
+    pub dashes: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClientMessage {
+    Hello,
+    Input(InputFrame),
+    Goodbye,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EntityKind {
+    Player(u8),
+    Enemy,
+    Star,
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EntityState {
+    pub id: u64,
+    pub kind: EntityKind,
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub tick: u32,
+    pub time: f32,
+    pub scores: [u32; MAX_PLAYERS],
+    pub entities: Vec<EntityState>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    Welcome { player: u8, arena_size: Vec2, wraps: bool },
+    Full,
+    Snapshot(Snapshot),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PeerInput {
+    pub direction: Vec2,
+    pub dash: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PeerMessage {
+    Hello {
+        seed: u64,
+    },
+    Inputs {
+        ack: u32,
+        start: u32,
+        inputs: Vec<PeerInput>,
+    },
+    Checksum {
+        tick: u32,
+        checksum: u64,
+    },
+}
+
+impl ClientMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        match self {
+            ClientMessage::Hello => writer.u8(0),
+            ClientMessage::Input(input) => {
+                writer.u8(1);
+                writer.u32(input.sequence);
+                writer.vec2(input.direction);
+                writer.u16(input.dashes);
+            }
+            ClientMessage::Goodbye => writer.u8(2),
+        }
+        writer.bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes)?;
+        match reader.u8()? {
+            0 => Some(ClientMessage::Hello),
+            1 => Some(ClientMessage::Input(InputFrame {
+                sequence: reader.u32()?,
+                direction: reader.direction()?,
+                dashes: reader.u16()?,
+            })),
+            2 => Some(ClientMessage::Goodbye),
+            _ => None,
+        }
+    }
+}
+
+impl ServerMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        match self {
+            ServerMessage::Welcome {
+                player,
+                arena_size,
+                wraps,
+            } => {
+                writer.u8(0);
+                writer.u8(*player);
+                writer.vec2(*arena_size);
+                writer.u8(*wraps as u8);
+            }
+            ServerMessage::Full => writer.u8(1),
+            ServerMessage::Snapshot(snapshot) => {
+                writer.u8(2);
+                writer.u32(snapshot.tick);
+                writer.f32(snapshot.time);
+                for score in snapshot.scores {
+                    writer.u32(score);
+                }
+                writer.u16(snapshot.entities.len() as u16);
+                for entity in snapshot.entities.iter() {
+                    writer.u64(entity.id);
+                    match entity.kind {
+                        EntityKind::Player(index) => {
+                            writer.u8(0);
+                            writer.u8(index);
+                        }
+                        EntityKind::Enemy => writer.u8(1),
+                        EntityKind::Star => writer.u8(2),
+                        EntityKind::Other => writer.u8(3),
+                    }
+                    writer.vec2(entity.position);
+                    writer.f32(entity.radius);
+                }
+            }
+        }
+        writer.bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes)?;
+        match reader.u8()? {
+            0 => Some(ServerMessage::Welcome {
+                player: reader.u8()?,
+                arena_size: reader.vec2()?,
+                wraps: reader.u8()? != 0,
+            }),
+            1 => Some(ServerMessage::Full),
+            2 => {
+                let tick = reader.u32()?;
+                let time = reader.f32()?;
+                let mut scores = [0; MAX_PLAYERS];
+                for score in scores.iter_mut() {
+                    *score = reader.u32()?;
+                }
+                let count = reader.u16()?;
+                let entities = (0..count)
+                    .map(|_| {
+                        let id = reader.u64()?;
+                        let kind = match reader.u8()? {
+                            0 => EntityKind::Player(reader.u8()?),
+                            1 => EntityKind::Enemy,
+                            2 => EntityKind::Star,
+                            3 => EntityKind::Other,
+                            _ => return None,
+                        };
+                        Some(EntityState {
+                            id,
+                            kind,
+                            position: reader.vec2()?,
+                            radius: reader.f32()?,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(ServerMessage::Snapshot(Snapshot {
+                    tick,
+                    time,
+                    scores,
+                    entities,
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl PeerMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        match self {
+            PeerMessage::Hello { seed } => {
+                writer.u8(0);
+                writer.u64(*seed);
+            }
+            PeerMessage::Inputs { ack, start, inputs } => {
+                writer.u8(1);
+                writer.u32(*ack);
+                writer.u32(*start);
+                writer.u16(inputs.len() as u16);
+                for input in inputs.iter() {
+                    writer.vec2(input.direction);
+                    writer.u8(input.dash as u8);
+                }
+            }
+            PeerMessage::Checksum { tick, checksum } => {
+                writer.u8(2);
+                writer.u32(*tick);
+                writer.u64(*checksum);
+            }
+        }
+        writer.bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes)?;
+        match reader.u8()? {
+            0 => Some(PeerMessage::Hello { seed: reader.u64()? }),
+            1 => {
+                let ack = reader.u32()?;
+                let start = reader.u32()?;
+                let count = reader.u16()?;
+                let inputs = (0..count)
+                    .map(|_| {
+                        Some(PeerInput {
+                            direction: reader.direction()?,
+                            dash: reader.u8()? != 0,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(PeerMessage::Inputs { ack, start, inputs })
+            }
+            2 => Some(PeerMessage::Checksum {
+                tick: reader.u32()?,
+                checksum: reader.u64()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        let mut writer = Self { bytes: Vec::new() };
+        writer.u16(PROTOCOL_ID);
+        writer
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn vec2(&mut self, value: Vec2) {
+        self.f32(value.x);
+        self.f32(value.y);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Option<Self> {
+        let mut reader = Self { bytes };
+        (reader.u16()? == PROTOCOL_ID).then_some(reader)
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.bytes.split_first_chunk::<N>()?;
+        self.bytes = rest;
+        Some(*head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|[value]| value)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.take().map(f32::from_le_bytes)
+    }
+
+    fn vec2(&mut self) -> Option<Vec2> {
+        Some(Vec2::new(self.f32()?, self.f32()?))
+    }
+
+    // Movement input comes straight from a remote machine, so it is kept to a finite unit vector.
+    fn direction(&mut self) -> Option<Vec2> {
+        let direction = self.vec2()?;
+        Some(if direction.is_finite() {
+            direction.clamp_length_max(1.0)
+        } else {
+            Vec2::ZERO
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> ServerMessage {
+        ServerMessage::Snapshot(Snapshot {
+            tick: 42,
+            time: 12.5,
+            scores: [3; MAX_PLAYERS],
+            entities: vec![
+                EntityState {
+                    id: 7,
+                    kind: EntityKind::Player(1),
+                    position: Vec2::new(10.0, 20.0),
+                    radius: 32.0,
+                },
+                EntityState {
+                    id: u64::MAX,
+                    kind: EntityKind::Enemy,
+                    position: Vec2::new(-4.5, 600.0),
+                    radius: 32.0,
+                },
+                EntityState {
+                    id: 9,
+                    kind: EntityKind::Star,
+                    position: Vec2::ZERO,
+                    radius: 15.0,
+                },
+            ],
+        })
+    }
+
+    #[test]
+    fn client_messages_round_trip() {
+        let messages = [
+            ClientMessage::Hello,
+            ClientMessage::Input(InputFrame {
+                sequence: 1234,
+                direction: Vec2::new(0.6, -0.8),
+                dashes: 3,
+            }),
+            ClientMessage::Goodbye,
+        ];
+
+        for message in messages {
+            assert_eq!(ClientMessage::decode(&message.encode()), Some(message));
+        }
+    }
+
+    #[test]
+    fn server_messages_round_trip() {
+        let messages = [
+            ServerMessage::Welcome {
+                player: 2,
+                arena_size: Vec2::new(1280.0, 720.0),
+                wraps: true,
+            },
+            ServerMessage::Full,
+            snapshot(),
+        ];
+
+        for message in messages {
+            assert_eq!(ServerMessage::decode(&message.encode()), Some(message));
+        }
+    }
+
+    #[test]
+    fn peer_messages_round_trip() {
+        let messages = [
+            PeerMessage::Hello {
+                seed: 0x0123_4567_89ab_cdef,
+            },
+            PeerMessage::Inputs {
+                ack: 17,
+                start: 20,
+                inputs: vec![
+                    PeerInput {
+                        direction: Vec2::new(-1.0, 0.0),
+                        dash: true,
+                    },
+                    PeerInput::default(),
+                ],
+            },
+            PeerMessage::Checksum {
+                tick: 30,
+                checksum: u64::MAX,
+            },
+        ];
+
+        for message in messages {
+            assert_eq!(PeerMessage::decode(&message.encode()), Some(message));
+        }
+    }
+
+    #[test]
+    fn peer_input_directions_are_sanitized() {
+        let message = PeerMessage::Inputs {
+            ack: 0,
+            start: 0,
+            inputs: vec![
+                PeerInput {
+                    direction: Vec2::new(f32::NEG_INFINITY, 1.0),
+                    dash: false,
+                },
+                PeerInput {
+                    direction: Vec2::new(0.0, -5.0),
+                    dash: false,
+                },
+            ],
+        };
+
+        let Some(PeerMessage::Inputs { inputs, .. }) = PeerMessage::decode(&message.encode()) else {
+            panic!("Inputs did not decode");
+        };
+        assert_eq!(inputs[0].direction, Vec2::ZERO);
+        assert_eq!(inputs[1].direction, Vec2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn input_directions_are_sanitized() {
+        let decode = |direction| {
+            let message = ClientMessage::Input(InputFrame { direction, ..default() });
+            match ClientMessage::decode(&message.encode()) {
+                Some(ClientMessage::Input(input)) => input.direction,
+                other => panic!("Unexpected message: {:?}", other),
+            }
+        };
+
+        assert_eq!(decode(Vec2::new(f32::NAN, 0.0)), Vec2::ZERO);
+        assert_eq!(decode(Vec2::new(0.0, f32::INFINITY)), Vec2::ZERO);
+        assert!((decode(Vec2::new(300.0, 400.0)) - Vec2::new(0.6, 0.8)).length() < 1e-6);
+    }
+
+    #[test]
+    fn truncated_messages_are_rejected() {
+        let client_bytes = ClientMessage::Input(InputFrame::default()).encode();
+        for length in 0..client_bytes.len() {
+            assert_eq!(ClientMessage::decode(&client_bytes[..length]), None);
+        }
+
+        let server_bytes = snapshot().encode();
+        for length in 0..server_bytes.len() {
+            assert_eq!(ServerMessage::decode(&server_bytes[..length]), None);
+        }
+
+        let peer_bytes = PeerMessage::Inputs {
+            ack: 1,
+            start: 2,
+            inputs: vec![PeerInput::default(); 3],
+        }
+        .encode();
+        for length in 0..peer_bytes.len() {
+            assert_eq!(PeerMessage::decode(&peer_bytes[..length]), None);
+        }
+    }
+
+    #[test]
+    fn wrong_protocol_id_is_rejected() {
+        let mut bytes = ClientMessage::Hello.encode();
+        bytes[0] ^= 0xff;
+        assert_eq!(ClientMessage::decode(&bytes), None);
+
+        let mut bytes = ServerMessage::Full.encode();
+        bytes[1] ^= 0xff;
+        assert_eq!(ServerMessage::decode(&bytes), None);
+
+        let mut bytes = PeerMessage::Hello { seed: 1 }.encode();
+        bytes[0] ^= 0xff;
+        assert_eq!(PeerMessage::decode(&bytes), None);
+    }
+}
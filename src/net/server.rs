@@ -0,0 +1,246 @@
+use super::NetConfig;
+use super::protocol::{ClientMessage, EntityKind, EntityState, ServerMessage, Snapshot};
+use super::transport::Transport;
+use crate::app_state::AppState;
+use crate::game::{
+    Arena, Collider, Controls, Enemy, Levels, MAX_PLAYERS, Party, Player, Projectile, RemoteInput, Score, Star,
+};
+use bevy::prelude::*;
+use std::net::SocketAddr;
+
+const SNAPSHOT_RATE: f32 = 20.0;
+const CLIENT_TIMEOUT: f32 = 5.0;
+const MAX_SNAPSHOT_ENTITIES: usize = 256;
+
+pub struct ServerPlugin(pub NetConfig);
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.0)
+            .insert_resource(Party::Coop)
+            .add_systems(Startup, open_server)
+            .add_systems(
+                Update,
+                (
+                    receive_client_messages,
+                    drop_silent_clients,
+                    start_match.run_if(not(in_state(AppState::Game))),
+                    send_snapshots,
+                    flush_server,
+                )
+                    .chain()
+                    .run_if(resource_exists::<Server>),
+            )
+            .add_observer(take_remote_control);
+    }
+}
+
+struct ConnectedClient {
+    address: SocketAddr,
+    player: u8,
+    last_heard: f32,
+    sequence: u32,
+    dashes: u16,
+}
+
+#[derive(Resource)]
+struct Server {
+    transport: Transport,
+    clients: Vec<ConnectedClient>,
+    snapshot_timer: Timer,
+    tick: u32,
+}
+
+fn open_server(mut commands: Commands, config: Res<NetConfig>, mut app_exit_event_writer: EventWriter<AppExit>) {
+    match Transport::bind(config.address, config.conditioner) {
+        Ok(transport) => {
+            info!("Listening on {}", config.address);
+            commands.insert_resource(Server {
+                transport,
+                clients: Vec::new(),
+                snapshot_timer: Timer::from_seconds(1.0 / SNAPSHOT_RATE, TimerMode::Repeating),
+                tick: 0,
+            });
+        }
+        Err(error) => {
+            error!("Failed to listen on {}: {}", config.address, error);
+            app_exit_event_writer.write(AppExit::error());
+        }
+    }
+}
+
+fn take_remote_control(trigger: Trigger<OnAdd, Player>, mut commands: Commands) {
+    commands
+        .entity(trigger.target())
+        .insert((Controls::Remote, RemoteInput::default()));
+}
+
+fn start_match(
+    server: Res<Server>,
+    levels: Option<Res<Levels>>,
+    asset_server: Res<AssetServer>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    let loaded = levels.is_some_and(|levels| asset_server.is_loaded_with_dependencies(&levels.default));
+    if loaded && !server.clients.is_empty() {
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn receive_client_messages(
+    mut server: ResMut<Server>,
+    mut player_query: Query<(&Player, &mut RemoteInput)>,
+    arena: Res<Arena>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+
+    for (address, bytes) in server.transport.receive() {
+        let Some(message) = ClientMessage::decode(&bytes) else {
+            continue;
+        };
+
+        let known = server.clients.iter().position(|client| client.address == address);
+        match (message, known) {
+            (ClientMessage::Hello, known) => {
+                let player = match known {
+                    Some(index) => Some(server.clients[index].player),
+                    None => (0..MAX_PLAYERS as u8)
+                        .find(|player| server.clients.iter().all(|client| client.player != *player)),
+                };
+
+                let reply = match player {
+                    Some(player) => {
+                        if known.is_none() {
+                            info!("Player {} joined from {}", player + 1, address);
+                            server.clients.push(ConnectedClient {
+                                address,
+                                player,
+                                last_heard: now,
+                                sequence: 0,
+                                dashes: 0,
+                            });
+                        }
+                        ServerMessage::Welcome {
+                            player,
+                            arena_size: arena.size(),
+                            wraps: arena.wraps(),
+                        }
+                    }
+                    None => ServerMessage::Full,
+                };
+                server.transport.send(address, reply.encode());
+            }
+            (ClientMessage::Input(input), Some(index)) => {
+                let client = &mut server.clients[index];
+                client.last_heard = now;
+                if (input.sequence.wrapping_sub(client.sequence) as i32) <= 0 {
+                    continue;
+                }
+
+                let dashed = input.dashes != client.dashes;
+                client.sequence = input.sequence;
+                client.dashes = input.dashes;
+                for (player, mut remote) in player_query.iter_mut() {
+                    if player.index == client.player as usize {
+                        remote.direction = input.direction;
+                        remote.dash |= dashed;
+                    }
+                }
+            }
+            (ClientMessage::Goodbye, Some(index)) => {
+                let client = server.clients.remove(index);
+                info!("Player {} left", client.player + 1);
+                release_player(&mut player_query, client.player);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn drop_silent_clients(
+    mut server: ResMut<Server>,
+    mut player_query: Query<(&Player, &mut RemoteInput)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+    let (silent, connected) = std::mem::take(&mut server.clients)
+        .into_iter()
+        .partition::<Vec<_>, _>(|client| now - client.last_heard > CLIENT_TIMEOUT);
+    server.clients = connected;
+
+    for client in silent {
+        info!("Player {} timed out", client.player + 1);
+        release_player(&mut player_query, client.player);
+    }
+}
+
+fn release_player(player_query: &mut Query<(&Player, &mut RemoteInput)>, player: u8) {
+    for (_, mut remote) in player_query
+        .iter_mut()
+        .filter(|(candidate, _)| candidate.index == player as usize)
+    {
+        *remote = RemoteInput::default();
+    }
+}
+
+type ReplicatedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Collider,
+        Option<&'static Player>,
+        Has<Enemy>,
+        Has<Star>,
+        Option<&'static Projectile>,
+    ),
+>;
+
+fn send_snapshots(mut server: ResMut<Server>, replicated_query: ReplicatedQuery, score: Res<Score>, time: Res<Time>) {
+    server.tick = server.tick.wrapping_add(1);
+    if !server.snapshot_timer.tick(time.delta()).just_finished() || server.clients.is_empty() {
+        return;
+    }
+
+    // Parked pool projectiles are skipped, and players go first so the entity cap never drops them.
+    let mut replicated: Vec<_> = replicated_query
+        .iter()
+        .filter(|(.., projectile)| projectile.is_none_or(|projectile| projectile.is_active()))
+        .collect();
+    replicated.sort_by_key(|(_, _, _, player, ..)| player.is_none());
+
+    let entities = replicated
+        .into_iter()
+        .map(|(entity, transform, collider, player, enemy, star, _)| EntityState {
+            id: entity.to_bits(),
+            kind: match (player, enemy, star) {
+                (Some(player), ..) => EntityKind::Player(player.index as u8),
+                (_, true, _) => EntityKind::Enemy,
+                (_, _, true) => EntityKind::Star,
+                _ => EntityKind::Other,
+            },
+            position: transform.translation.truncate(),
+            radius: collider.radius,
+        })
+        .take(MAX_SNAPSHOT_ENTITIES)
+        .collect();
+
+    let bytes = ServerMessage::Snapshot(Snapshot {
+        tick: server.tick,
+        time: time.elapsed_secs(),
+        scores: score.players,
+        entities,
+    })
+    .encode();
+
+    let addresses: Vec<SocketAddr> = server.clients.iter().map(|client| client.address).collect();
+    for address in addresses {
+        server.transport.send(address, bytes.clone());
+    }
+}
+
+fn flush_server(mut server: ResMut<Server>) {
+    server.transport.flush();
+}
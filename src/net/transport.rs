@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use rand::random;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinkConditioner {
+    pub latency: Duration,
+    pub jitter: Duration,
+    pub loss: f32,
+}
+
+struct DelayedDatagram {
+    send_at: Instant,
+    address: SocketAddr,
+    bytes: Vec<u8>,
+}
+
+pub struct Transport {
+    socket: UdpSocket,
+    conditioner: LinkConditioner,
+    outgoing: Vec<DelayedDatagram>,
+    buffer: Vec<u8>,
+}
+
+impl Transport {
+    pub fn bind(address: SocketAddr, conditioner: LinkConditioner) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            conditioner,
+            outgoing: Vec::new(),
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        })
+    }
+
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        self.socket.local_addr().ok()
+    }
+
+    pub fn send(&mut self, address: SocketAddr, bytes: Vec<u8>) {
+        if random::<f32>() < self.conditioner.loss {
+            return;
+        }
+
+        let delay = self.conditioner.latency + self.conditioner.jitter.mul_f32(random::<f32>());
+        self.outgoing.push(DelayedDatagram {
+            send_at: Instant::now() + delay,
+            address,
+            bytes,
+        });
+    }
+
+    pub fn flush(&mut self) {
+        let now = Instant::now();
+        let (due, pending) = std::mem::take(&mut self.outgoing)
+            .into_iter()
+            .partition(|datagram| datagram.send_at <= now);
+        self.outgoing = pending;
+
+        for datagram in due {
+            if let Err(error) = self.socket.send_to(&datagram.bytes, datagram.address) {
+                warn!("Failed to send to {}: {}", datagram.address, error);
+            }
+        }
+    }
+
+    pub fn receive(&mut self) -> Vec<(SocketAddr, Vec<u8>)> {
+        let mut datagrams = Vec::new();
+        loop {
+            match self.socket.recv_from(&mut self.buffer) {
+                Ok((length, address)) => datagrams.push((address, self.buffer[..length].to_vec())),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::ConnectionReset => continue,
+                Err(error) => {
+                    warn!("Failed to receive: {}", error);
+                    break;
+                }
+            }
+        }
+        datagrams
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+    }
+}
+
+#[derive(Resource)]
+pub struct Settings {
+    pub reduce_motion: bool,
+    pub camera_scaling: CameraScaling,
+    pub dash_key: KeyCode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            reduce_motion: false,
+            camera_scaling: CameraScaling::default(),
+            dash_key: KeyCode::ShiftLeft,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+pub enum CameraScaling {
+    #[default]
+    Letterbox,
+    Fit,
+}
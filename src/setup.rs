@@ -1,22 +1,77 @@
+use crate::camera::CameraEffects;
+use crate::game::Arena;
+use crate::sound::{LISTENER_GAP, SPATIAL_SCALE};
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::audio::{AudioPlugin, SpatialScale};
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::WgpuSettings;
+use bevy::render::view::RenderLayers;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use std::time::Duration;
+
+const HEADLESS_TICK_RATE: f64 = 60.0;
 
 pub struct SetupPlugin;
 
 impl Plugin for SetupPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(DefaultPlugins).add_systems(Startup, spawn_camera);
+        app.add_plugins(DefaultPlugins.set(AudioPlugin {
+            default_spatial_scale: SpatialScale::new_2d(SPATIAL_SCALE),
+            ..default()
+        }))
+        .add_systems(Startup, spawn_camera);
+    }
+}
+
+pub struct HeadlessSetupPlugin;
+
+impl Plugin for HeadlessSetupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
+        )
+        .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1.0 / HEADLESS_TICK_RATE,
+        )));
     }
 }
 
-fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<PrimaryWindow>>) {
-    let window = window_query.single().unwrap();
+fn spawn_camera(mut commands: Commands, arena: Res<Arena>) {
+    let translation = arena.center().with_z(1.0);
     commands.spawn((
         Camera2d,
         Camera {
             clear_color: ClearColorConfig::Custom(Color::srgb_u8(11, 35, 35)),
             ..default()
         },
-        Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 1.0),
+        Transform::from_translation(translation),
+        SpatialListener::new(LISTENER_GAP),
+        CameraEffects::new(translation),
+    ));
+    // Renders nothing; it only clears the letterbox bars around the main camera's viewport.
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: -1,
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            ..default()
+        },
+        RenderLayers::none(),
     ));
 }
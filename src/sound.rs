@@ -0,0 +1,31 @@
+use crate::game::RollbackFrame;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+// World units are pixels, so positions are scaled down before they reach the spatial sink.
+// The sink pans by the difference in ear distances and scales each ear by the inverse square of its
+// scaled distance, capped at full volume, so a sound is at full strength only within 400px of an ear.
+pub const SPATIAL_SCALE: f32 = 1.0 / 400.0;
+pub const LISTENER_GAP: f32 = 400.0;
+
+#[derive(SystemParam)]
+pub struct SpatialSounds<'w> {
+    asset_server: Res<'w, AssetServer>,
+    rollback_frame: Option<Res<'w, RollbackFrame>>,
+}
+
+impl SpatialSounds<'_> {
+    pub fn play(&self, commands: &mut Commands, path: &'static str, translation: Vec3) {
+        if self.rollback_frame.as_ref().is_none_or(|frame| !frame.replaying) {
+            commands.spawn(spatial_sound(&self.asset_server, path, translation));
+        }
+    }
+}
+
+fn spatial_sound(asset_server: &AssetServer, path: &'static str, translation: Vec3) -> impl Bundle {
+    (
+        AudioPlayer::<AudioSource>(asset_server.load(path)),
+        PlaybackSettings::DESPAWN.with_spatial(true),
+        Transform::from_translation(translation),
+    )
+}
@@ -1,9 +1,19 @@
+mod daily_menu;
 mod game_over_menu;
+mod level_select;
 mod main_menu;
+mod mode_select;
+mod versus_menu;
+mod victory_menu;
 
 use bevy::prelude::*;
+use daily_menu::DailyMenuPlugin;
 use game_over_menu::GameOverMenuPlugin;
+use level_select::LevelSelectPlugin;
 use main_menu::MainMenuPlugin;
+use mode_select::ModeSelectPlugin;
+use versus_menu::VersusMenuPlugin;
+use victory_menu::VictoryMenuPlugin;
 
 const BUTTON_COLOR: Srgba = Srgba::new(0.15, 0.15, 0.15, 1.0);
 const HOVERED_BUTTON_COLOR: Srgba = Srgba::new(0.25, 0.25, 0.25, 1.0);
@@ -14,7 +24,12 @@ pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(MainMenuPlugin)
+            .add_plugins(ModeSelectPlugin)
+            .add_plugins(LevelSelectPlugin)
+            .add_plugins(DailyMenuPlugin)
             .add_plugins(GameOverMenuPlugin)
+            .add_plugins(VersusMenuPlugin)
+            .add_plugins(VictoryMenuPlugin)
             .add_systems(Update, interact_with_buttons);
     }
 }
@@ -101,6 +116,23 @@ pub fn button_bundle(asset_server: &Res<AssetServer>, text: &str, button: impl C
     )
 }
 
+pub fn small_button_bundle(asset_server: &Res<AssetServer>, text: &str, button: impl Component) -> impl Bundle {
+    (
+        Button,
+        Node {
+            min_width: Val::Px(36.0),
+            height: Val::Px(36.0),
+            padding: UiRect::horizontal(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR.into()),
+        button,
+        children![text_bundle(asset_server, text, 18.0)],
+    )
+}
+
 pub fn text_bundle(asset_server: &Res<AssetServer>, text: &str, font_size: f32) -> impl Bundle {
     (
         Text::new(text),
@@ -116,3 +148,13 @@ pub fn text_bundle(asset_server: &Res<AssetServer>, text: &str, font_size: f32)
         },
     )
 }
+
+pub fn format_time(seconds: f32) -> String {
+    let hundredths = (seconds.max(0.0) * 100.0).round() as u32;
+    format!(
+        "{}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
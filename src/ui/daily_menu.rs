@@ -0,0 +1,95 @@
+use super::{button_bundle, container_node, format_time, text_bundle};
+use crate::app_state::AppState;
+use crate::game::{DailyChallenge, DailyHistory, GameMode, Mutator, SelectedLevel};
+use bevy::prelude::*;
+
+const HISTORY_LENGTH: usize = 7;
+
+pub struct DailyMenuPlugin;
+
+impl Plugin for DailyMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Daily), spawn_daily_menu)
+            .add_systems(OnExit(AppState::Daily), despawn_daily_menu)
+            .add_systems(Update, (interact_with_start_button, interact_with_back_button));
+    }
+}
+
+#[derive(Component)]
+struct DailyMenu;
+
+#[derive(Component)]
+struct StartButton;
+
+#[derive(Component)]
+struct BackButton;
+
+fn spawn_daily_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut challenge: ResMut<DailyChallenge>,
+    history: Res<DailyHistory>,
+) {
+    challenge.refresh();
+
+    let modifiers = challenge
+        .mutators
+        .iter()
+        .map(|mutator| Mutator::label(*mutator))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let status = match history.result_for(&challenge.date) {
+        Some(result) => format!("Today's score: {} (practice only)", result.score),
+        None => "One scored attempt available".to_string(),
+    };
+
+    commands.spawn((DailyMenu, container_node())).with_children(|parent| {
+        parent.spawn(text_bundle(&asset_server, "Daily Challenge", 48.0));
+        parent.spawn(text_bundle(&asset_server, &challenge.date, 24.0));
+        parent.spawn(text_bundle(&asset_server, &format!("Modifiers: {}", modifiers), 24.0));
+        parent.spawn(text_bundle(&asset_server, &status, 24.0));
+
+        for result in history.results.iter().rev().take(HISTORY_LENGTH) {
+            parent.spawn(text_bundle(
+                &asset_server,
+                &format!("{}: {} ({})", result.date, result.score, format_time(result.seconds)),
+                18.0,
+            ));
+        }
+
+        parent.spawn(button_bundle(&asset_server, "Start", StartButton));
+        parent.spawn(button_bundle(&asset_server, "Back", BackButton));
+    });
+}
+
+fn despawn_daily_menu(mut commands: Commands, query: Query<Entity, With<DailyMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn interact_with_start_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<StartButton>)>,
+    mut game_mode: ResMut<GameMode>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        *game_mode = GameMode::Daily;
+        *selected_level = SelectedLevel::Endless;
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn interact_with_back_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::MainMenu);
+    }
+}
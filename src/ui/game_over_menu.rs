@@ -1,6 +1,6 @@
-use super::{button_bundle, container_node, text_bundle};
+use super::{button_bundle, container_node, format_time, text_bundle};
 use crate::app_state::AppState;
-use crate::game::Score;
+use crate::game::{GameMode, HighScores, MatchStats, Score, SelectedLevel};
 use bevy::prelude::*;
 
 pub struct GameOverMenuPlugin;
@@ -32,18 +32,44 @@ struct MainMenuButton;
 #[derive(Component)]
 struct QuitButton;
 
-fn spawn_game_over_menu(mut commands: Commands, asset_server: Res<AssetServer>, score: Res<Score>) {
-    commands.spawn((
-        GameOverMenu,
-        container_node(),
-        children![
-            text_bundle(&asset_server, "Game Over", 48.0),
-            text_bundle(&asset_server, &format!("Score: {}", score.value), 24.0),
-            button_bundle(&asset_server, "Main Menu", MainMenuButton),
-            button_bundle(&asset_server, "Restart", RestartButton),
-            button_bundle(&asset_server, "Quit", QuitButton),
-        ],
-    ));
+fn spawn_game_over_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    score: Res<Score>,
+    match_stats: Res<MatchStats>,
+    game_mode: Res<GameMode>,
+    high_scores: Res<HighScores>,
+    selected_level: Res<SelectedLevel>,
+) {
+    let title = match *game_mode {
+        GameMode::TimeAttack => "Time Up",
+        GameMode::Classic | GameMode::Survival | GameMode::Daily => "Game Over",
+        GameMode::Versus => return,
+    };
+
+    commands
+        .spawn((GameOverMenu, container_node()))
+        .with_children(|parent| {
+            parent.spawn(text_bundle(&asset_server, title, 48.0));
+            parent.spawn(text_bundle(&asset_server, &format!("Score: {}", score.total()), 24.0));
+            if *game_mode == GameMode::Survival {
+                parent.spawn(text_bundle(
+                    &asset_server,
+                    &format!("Survived: {}", format_time(match_stats.seconds)),
+                    24.0,
+                ));
+            }
+            if matches!(*selected_level, SelectedLevel::Endless) {
+                parent.spawn(text_bundle(
+                    &asset_server,
+                    &format!("{} best: {}", game_mode.label(), high_scores.best(*game_mode)),
+                    24.0,
+                ));
+            }
+            parent.spawn(button_bundle(&asset_server, "Main Menu", MainMenuButton));
+            parent.spawn(button_bundle(&asset_server, "Restart", RestartButton));
+            parent.spawn(button_bundle(&asset_server, "Quit", QuitButton));
+        });
 }
 
 fn despawn_game_over_menu(mut commands: Commands, query: Query<Entity, With<GameOverMenu>>) {
@@ -0,0 +1,78 @@
+use super::{button_bundle, container_node, text_bundle};
+use crate::app_state::AppState;
+use crate::game::{CampaignProgress, GameMode, Levels, SelectedLevel};
+use bevy::prelude::*;
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::LevelSelect), spawn_level_select)
+            .add_systems(OnExit(AppState::LevelSelect), despawn_level_select)
+            .add_systems(Update, (interact_with_level_buttons, interact_with_back_button));
+    }
+}
+
+#[derive(Component)]
+struct LevelSelect;
+
+#[derive(Component)]
+struct LevelButton(usize);
+
+#[derive(Component)]
+struct BackButton;
+
+fn spawn_level_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    levels: Res<Levels>,
+    progress: Res<CampaignProgress>,
+) {
+    commands.spawn((LevelSelect, container_node())).with_children(|parent| {
+        parent.spawn(text_bundle(&asset_server, "Campaign", 48.0));
+
+        for index in 0..levels.campaign.len() {
+            let label = if progress.is_unlocked(index) {
+                format!("Level {}", index + 1)
+            } else {
+                "Locked".to_string()
+            };
+            parent.spawn(button_bundle(&asset_server, &label, LevelButton(index)));
+        }
+
+        parent.spawn(button_bundle(&asset_server, "Back", BackButton));
+    });
+}
+
+fn despawn_level_select(mut commands: Commands, query: Query<Entity, With<LevelSelect>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn interact_with_level_buttons(
+    button_query: Query<(&Interaction, &LevelButton), Changed<Interaction>>,
+    progress: Res<CampaignProgress>,
+    mut game_mode: ResMut<GameMode>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, level_button) in button_query.iter() {
+        if *interaction == Interaction::Pressed && progress.is_unlocked(level_button.0) {
+            *game_mode = GameMode::Classic;
+            *selected_level = SelectedLevel::Campaign(level_button.0);
+            next_app_state.set(AppState::Game);
+        }
+    }
+}
+
+fn interact_with_back_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::MainMenu);
+    }
+}
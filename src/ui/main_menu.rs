@@ -1,14 +1,44 @@
 use super::{button_bundle, container_node, title_bundle};
 use crate::app_state::AppState;
+use crate::settings::{CameraScaling, Settings};
 use bevy::prelude::*;
 
+const RESERVED_KEYS: [KeyCode; 13] = [
+    KeyCode::Escape,
+    KeyCode::Space,
+    KeyCode::KeyG,
+    KeyCode::KeyM,
+    KeyCode::KeyW,
+    KeyCode::KeyA,
+    KeyCode::KeyS,
+    KeyCode::KeyD,
+    KeyCode::ArrowUp,
+    KeyCode::ArrowLeft,
+    KeyCode::ArrowDown,
+    KeyCode::ArrowRight,
+    KeyCode::ShiftRight,
+];
+
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu)
             .add_systems(OnExit(AppState::MainMenu), despawn_main_menu)
-            .add_systems(Update, (interact_with_play_button, interact_with_quit_button));
+            .add_systems(
+                Update,
+                (
+                    interact_with_play_button,
+                    interact_with_campaign_button,
+                    interact_with_daily_button,
+                    interact_with_editor_button,
+                    interact_with_reduce_motion_button,
+                    interact_with_camera_scaling_button,
+                    interact_with_dash_key_button,
+                    rebind_dash_key,
+                    interact_with_quit_button,
+                ),
+            );
     }
 }
 
@@ -18,21 +48,64 @@ struct MainMenu;
 #[derive(Component)]
 struct PlayButton;
 
+#[derive(Component)]
+struct CampaignButton;
+
+#[derive(Component)]
+struct DailyButton;
+
+#[derive(Component)]
+struct EditorButton;
+
+#[derive(Component)]
+struct ReduceMotionButton;
+
+#[derive(Component)]
+struct CameraScalingButton;
+
+#[derive(Component)]
+struct DashKeyButton {
+    listening: bool,
+}
+
 #[derive(Component)]
 struct QuitButton;
 
-fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<Settings>) {
     commands.spawn((
         container_node(),
         MainMenu,
         children![
             title_bundle(&asset_server),
             button_bundle(&asset_server, "Play", PlayButton),
+            button_bundle(&asset_server, "Campaign", CampaignButton),
+            button_bundle(&asset_server, "Daily", DailyButton),
+            button_bundle(&asset_server, "Editor", EditorButton),
+            (
+                settings_row_node(),
+                children![
+                    button_bundle(&asset_server, &reduce_motion_label(&settings), ReduceMotionButton),
+                    button_bundle(&asset_server, &camera_scaling_label(&settings), CameraScalingButton),
+                    button_bundle(
+                        &asset_server,
+                        &dash_key_label(&settings),
+                        DashKeyButton { listening: false }
+                    ),
+                ],
+            ),
             button_bundle(&asset_server, "Quit", QuitButton),
         ],
     ));
 }
 
+fn settings_row_node() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        column_gap: Val::Px(8.0),
+        ..default()
+    }
+}
+
 fn despawn_main_menu(mut commands: Commands, main_menu_query: Query<Entity, With<MainMenu>>) {
     for entity in main_menu_query.iter() {
         commands.entity(entity).despawn();
@@ -43,13 +116,154 @@ fn interact_with_play_button(
     mut button_query: Query<&Interaction, (Changed<Interaction>, With<PlayButton>)>,
     mut next_app_state: ResMut<NextState<AppState>>,
 ) {
-    if let Ok(interaction) = button_query.single_mut() {
-        if *interaction == Interaction::Pressed {
-            next_app_state.set(AppState::Game);
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::ModeSelect);
+    }
+}
+
+fn interact_with_campaign_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<CampaignButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::LevelSelect);
+    }
+}
+
+fn interact_with_daily_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<DailyButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::Daily);
+    }
+}
+
+fn interact_with_editor_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<EditorButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::Editor);
+    }
+}
+
+type ReduceMotionButtonQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static Children), (Changed<Interaction>, With<ReduceMotionButton>)>;
+
+fn interact_with_reduce_motion_button(
+    button_query: ReduceMotionButtonQuery,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<Settings>,
+) {
+    if let Ok((interaction, children)) = button_query.single()
+        && *interaction == Interaction::Pressed
+    {
+        settings.reduce_motion = !settings.reduce_motion;
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = reduce_motion_label(&settings);
+            }
         }
     }
 }
 
+fn reduce_motion_label(settings: &Settings) -> String {
+    if settings.reduce_motion {
+        "Motion: Reduced".to_string()
+    } else {
+        "Motion: Full".to_string()
+    }
+}
+
+type CameraScalingButtonQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static Children), (Changed<Interaction>, With<CameraScalingButton>)>;
+
+fn interact_with_camera_scaling_button(
+    button_query: CameraScalingButtonQuery,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<Settings>,
+) {
+    if let Ok((interaction, children)) = button_query.single()
+        && *interaction == Interaction::Pressed
+    {
+        settings.camera_scaling = match settings.camera_scaling {
+            CameraScaling::Letterbox => CameraScaling::Fit,
+            CameraScaling::Fit => CameraScaling::Letterbox,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = camera_scaling_label(&settings);
+            }
+        }
+    }
+}
+
+fn camera_scaling_label(settings: &Settings) -> String {
+    match settings.camera_scaling {
+        CameraScaling::Letterbox => "Letterbox".to_string(),
+        CameraScaling::Fit => "Fit View".to_string(),
+    }
+}
+
+type DashKeyButtonQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static mut DashKeyButton, &'static Children), Changed<Interaction>>;
+
+fn interact_with_dash_key_button(mut button_query: DashKeyButtonQuery, mut text_query: Query<&mut Text>) {
+    if let Ok((interaction, mut button, children)) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        button.listening = true;
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = "Press a key".to_string();
+            }
+        }
+    }
+}
+
+fn rebind_dash_key(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut button_query: Query<(&mut DashKeyButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<Settings>,
+) {
+    let Ok((mut button, children)) = button_query.single_mut() else {
+        return;
+    };
+
+    if !button.listening {
+        return;
+    }
+
+    let Some(key) = keyboard_input
+        .get_just_pressed()
+        .find(|key| !RESERVED_KEYS.contains(key))
+    else {
+        return;
+    };
+
+    button.listening = false;
+    settings.dash_key = *key;
+    for child in children.iter() {
+        if let Ok(mut text) = text_query.get_mut(child) {
+            **text = dash_key_label(&settings);
+        }
+    }
+}
+
+fn dash_key_label(settings: &Settings) -> String {
+    format!("Dash: {}", format!("{:?}", settings.dash_key).trim_start_matches("Key"))
+}
+
 fn interact_with_quit_button(
     mut app_exit_event_writer: EventWriter<AppExit>,
     mut button_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
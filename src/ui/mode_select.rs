@@ -0,0 +1,186 @@
+use super::{button_bundle, container_node, small_button_bundle, text_bundle};
+use crate::app_state::AppState;
+use crate::game::{GameMode, HighScores, Mutator, Party, SelectedLevel, SelectedMutators};
+use bevy::prelude::*;
+
+pub struct ModeSelectPlugin;
+
+impl Plugin for ModeSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::ModeSelect), spawn_mode_select)
+            .add_systems(OnExit(AppState::ModeSelect), despawn_mode_select)
+            .add_systems(
+                Update,
+                (
+                    interact_with_mode_buttons,
+                    interact_with_mutator_buttons,
+                    interact_with_party_button,
+                    interact_with_back_button,
+                ),
+            );
+    }
+}
+
+#[derive(Component)]
+struct ModeSelect;
+
+#[derive(Component)]
+struct ModeButton(GameMode);
+
+#[derive(Component)]
+struct MutatorButton(Mutator);
+
+#[derive(Component)]
+struct PartyButton;
+
+#[derive(Component)]
+struct MultiplierText;
+
+#[derive(Component)]
+struct BackButton;
+
+fn spawn_mode_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    high_scores: Res<HighScores>,
+    selected_mutators: Res<SelectedMutators>,
+    party: Res<Party>,
+) {
+    commands.spawn((ModeSelect, container_node())).with_children(|parent| {
+        parent.spawn(text_bundle(&asset_server, "Select Mode", 48.0));
+        parent.spawn(small_button_bundle(&asset_server, party.label(), PartyButton));
+
+        for mode in GameMode::ALL {
+            parent.spawn(button_bundle(&asset_server, mode.label(), ModeButton(mode)));
+            parent.spawn(text_bundle(
+                &asset_server,
+                &format!("Best: {}", high_scores.best(mode)),
+                20.0,
+            ));
+        }
+        parent.spawn(button_bundle(
+            &asset_server,
+            GameMode::Versus.label(),
+            ModeButton(GameMode::Versus),
+        ));
+
+        parent.spawn(text_bundle(&asset_server, "Mutators", 24.0));
+        parent
+            .spawn(Node {
+                max_width: Val::Px(600.0),
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                justify_content: JustifyContent::Center,
+                column_gap: Val::Px(4.0),
+                row_gap: Val::Px(4.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for mutator in Mutator::ALL {
+                    row.spawn(small_button_bundle(
+                        &asset_server,
+                        &mutator_label(mutator, &selected_mutators),
+                        MutatorButton(mutator),
+                    ));
+                }
+            });
+        parent.spawn((
+            MultiplierText,
+            text_bundle(&asset_server, &multiplier_label(&selected_mutators), 20.0),
+        ));
+
+        parent.spawn(button_bundle(&asset_server, "Back", BackButton));
+    });
+}
+
+fn mutator_label(mutator: Mutator, selected_mutators: &SelectedMutators) -> String {
+    if selected_mutators.contains(mutator) {
+        format!("{}: On", mutator.label())
+    } else {
+        format!("{}: Off", mutator.label())
+    }
+}
+
+fn multiplier_label(selected_mutators: &SelectedMutators) -> String {
+    format!("Score x{:.2}", selected_mutators.score_multiplier())
+}
+
+fn despawn_mode_select(mut commands: Commands, query: Query<Entity, With<ModeSelect>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn interact_with_mode_buttons(
+    button_query: Query<(&Interaction, &ModeButton), Changed<Interaction>>,
+    mut game_mode: ResMut<GameMode>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mode_button) in button_query.iter() {
+        if *interaction == Interaction::Pressed {
+            *game_mode = mode_button.0;
+            *selected_level = SelectedLevel::Endless;
+            next_app_state.set(AppState::Game);
+        }
+    }
+}
+
+fn interact_with_mutator_buttons(
+    button_query: Query<(&Interaction, &MutatorButton), Changed<Interaction>>,
+    label_query: Query<(&MutatorButton, &Children)>,
+    mut text_query: Query<&mut Text, Without<MultiplierText>>,
+    mut multiplier_text_query: Query<&mut Text, With<MultiplierText>>,
+    mut selected_mutators: ResMut<SelectedMutators>,
+) {
+    for (interaction, mutator_button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        // Toggling one mutator can switch off another it excludes, so every label is refreshed.
+        selected_mutators.toggle(mutator_button.0);
+        for (mutator_button, children) in label_query.iter() {
+            for child in children.iter() {
+                if let Ok(mut text) = text_query.get_mut(child) {
+                    **text = mutator_label(mutator_button.0, &selected_mutators);
+                }
+            }
+        }
+        for mut text in multiplier_text_query.iter_mut() {
+            **text = multiplier_label(&selected_mutators);
+        }
+    }
+}
+
+type PartyButtonQuery<'w, 's> =
+    Query<'w, 's, (&'static Interaction, &'static Children), (Changed<Interaction>, With<PartyButton>)>;
+
+fn interact_with_party_button(
+    button_query: PartyButtonQuery,
+    mut text_query: Query<&mut Text>,
+    mut party: ResMut<Party>,
+) {
+    if let Ok((interaction, children)) = button_query.single()
+        && *interaction == Interaction::Pressed
+    {
+        let index = Party::ALL.iter().position(|option| *option == *party).unwrap_or(0);
+        *party = Party::ALL[(index + 1) % Party::ALL.len()];
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = party.label().to_string();
+            }
+        }
+    }
+}
+
+fn interact_with_back_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,109 @@
+use super::{button_bundle, container_node, text_bundle};
+use crate::app_state::AppState;
+use crate::game::{GameMode, MatchStats, Score};
+use bevy::prelude::*;
+use std::cmp::Ordering;
+
+pub struct VersusMenuPlugin;
+
+impl Plugin for VersusMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::GameOver), spawn_versus_menu)
+            .add_systems(OnExit(AppState::GameOver), despawn_versus_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_rematch_button,
+                    interact_with_main_menu_button,
+                    interact_with_quit_button,
+                ),
+            );
+    }
+}
+
+#[derive(Component)]
+struct VersusMenu;
+
+#[derive(Component)]
+struct RematchButton;
+
+#[derive(Component)]
+struct MainMenuButton;
+
+#[derive(Component)]
+struct QuitButton;
+
+fn spawn_versus_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    score: Res<Score>,
+    match_stats: Res<MatchStats>,
+    game_mode: Res<GameMode>,
+) {
+    if *game_mode != GameMode::Versus {
+        return;
+    }
+
+    let [first, second] = score.players;
+    let title = match first.cmp(&second) {
+        Ordering::Greater => "Player 1 Wins",
+        Ordering::Less => "Player 2 Wins",
+        Ordering::Equal => "Draw",
+    };
+
+    commands.spawn((VersusMenu, container_node())).with_children(|parent| {
+        parent.spawn(text_bundle(&asset_server, title, 48.0));
+        parent.spawn(text_bundle(
+            &asset_server,
+            &format!("P1: {first}    P2: {second}"),
+            32.0,
+        ));
+        parent.spawn(text_bundle(
+            &asset_server,
+            &format!("Stars collected: {}", match_stats.stars_collected),
+            24.0,
+        ));
+        parent.spawn(button_bundle(&asset_server, "Rematch", RematchButton));
+        parent.spawn(button_bundle(&asset_server, "Main Menu", MainMenuButton));
+        parent.spawn(button_bundle(&asset_server, "Quit", QuitButton));
+    });
+}
+
+fn despawn_versus_menu(mut commands: Commands, query: Query<Entity, With<VersusMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn interact_with_rematch_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<RematchButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn interact_with_main_menu_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::MainMenu);
+    }
+}
+
+fn interact_with_quit_button(
+    mut app_exit_event_writer: EventWriter<AppExit>,
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        app_exit_event_writer.write(AppExit::Success);
+    }
+}
@@ -0,0 +1,132 @@
+use super::{button_bundle, container_node, format_time, text_bundle};
+use crate::app_state::AppState;
+use crate::game::{Level, Levels, MatchStats, Score, SelectedLevel};
+use bevy::prelude::*;
+
+pub struct VictoryMenuPlugin;
+
+impl Plugin for VictoryMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Victory), spawn_victory_menu)
+            .add_systems(OnExit(AppState::Victory), despawn_victory_menu)
+            .add_systems(
+                Update,
+                (
+                    interact_with_next_level_button,
+                    interact_with_restart_button,
+                    interact_with_main_menu_button,
+                    interact_with_quit_button,
+                ),
+            );
+    }
+}
+
+#[derive(Component)]
+struct VictoryMenu;
+
+#[derive(Component)]
+struct NextLevelButton;
+
+#[derive(Component)]
+struct RestartButton;
+
+#[derive(Component)]
+struct MainMenuButton;
+
+#[derive(Component)]
+struct QuitButton;
+
+fn spawn_victory_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    score: Res<Score>,
+    match_stats: Res<MatchStats>,
+    level: Res<Level>,
+    levels: Res<Levels>,
+    selected_level: Res<SelectedLevel>,
+) {
+    commands.spawn((VictoryMenu, container_node())).with_children(|parent| {
+        parent.spawn(text_bundle(&asset_server, "Victory", 48.0));
+        parent.spawn(text_bundle(&asset_server, &level.name, 32.0));
+        parent.spawn(text_bundle(&asset_server, &format!("Score: {}", score.total()), 24.0));
+        parent.spawn(text_bundle(
+            &asset_server,
+            &format!("Time: {}", format_time(match_stats.seconds)),
+            24.0,
+        ));
+        parent.spawn(text_bundle(
+            &asset_server,
+            &format!("Stars collected: {}", match_stats.stars_collected),
+            24.0,
+        ));
+
+        if next_level(&selected_level, &levels).is_some() {
+            parent.spawn(button_bundle(&asset_server, "Next Level", NextLevelButton));
+        }
+
+        parent.spawn(button_bundle(&asset_server, "Restart", RestartButton));
+        parent.spawn(button_bundle(&asset_server, "Main Menu", MainMenuButton));
+        parent.spawn(button_bundle(&asset_server, "Quit", QuitButton));
+    });
+}
+
+fn despawn_victory_menu(mut commands: Commands, query: Query<Entity, With<VictoryMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn next_level(selected_level: &SelectedLevel, levels: &Levels) -> Option<SelectedLevel> {
+    match *selected_level {
+        SelectedLevel::Campaign(index) if index + 1 < levels.campaign.len() => Some(SelectedLevel::Campaign(index + 1)),
+        _ => None,
+    }
+}
+
+fn interact_with_next_level_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<NextLevelButton>)>,
+    levels: Res<Levels>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+        && let Some(next_level) = next_level(&selected_level, &levels)
+    {
+        *selected_level = next_level;
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn interact_with_restart_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::Game);
+    }
+}
+
+fn interact_with_main_menu_button(
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuButton>)>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        next_app_state.set(AppState::MainMenu);
+    }
+}
+
+fn interact_with_quit_button(
+    mut app_exit_event_writer: EventWriter<AppExit>,
+    mut button_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
+) {
+    if let Ok(interaction) = button_query.single_mut()
+        && *interaction == Interaction::Pressed
+    {
+        app_exit_event_writer.write(AppExit::Success);
+    }
+}